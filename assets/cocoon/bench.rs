@@ -0,0 +1,229 @@
+//! Measures the latency of updates to a generated topology, at increasing scale.
+//!
+//! For each `--scales K,..` (default 1,2,4,8), runs `ovn`, loads the topology of
+//! `Sizes::from_args` scaled by K, and then times `--updates N` churn changes (default 200)
+//! twice: sent one fact at a time outside transactions, each timed from the request to its
+//! response, and as whole transactions, timed from `start` to the response to `commit`.
+//! Every `add` and `del` is an `insert!` or `remove!`: one epoch, and `worker.step()` until the
+//! probe passes it, so the single-fact numbers are mostly that path plus a pipe round trip.
+//!
+//! The results are one JSON document on stdout, with the peak resident memory of `ovn` after
+//! each scale, for tracking over time. `--ovn PATH` picks the binary (default: the `ovn` next
+//! to this one); arguments after `--` are passed to it.
+//!
+//!   cargo build --release && target/release/bench --switches 16 --ports 16 > bench.json
+
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::time::Instant;
+
+use serde_json as json;
+
+mod topology;
+
+use topology::{Sizes, Topology, Txn};
+
+/// The latency distribution of a batch of updates, in microseconds.
+#[derive(Serialize, Debug)]
+struct Latencies {
+    count: usize,
+    /// Updates answered with an error; they are timed all the same.
+    errors: usize,
+    mean_us: f64,
+    p50_us: f64,
+    p90_us: f64,
+    p99_us: f64,
+    max_us: f64
+}
+
+impl Latencies {
+    fn new(mut us: Vec<f64>, errors: usize) -> Latencies {
+        us.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let pct = |p: f64| if us.is_empty() { 0.0 } else { us[((us.len() - 1) as f64 * p).round() as usize] };
+        Latencies {
+            count: us.len(),
+            errors: errors,
+            mean_us: if us.is_empty() { 0.0 } else { us.iter().sum::<f64>() / us.len() as f64 },
+            p50_us: pct(0.5),
+            p90_us: pct(0.9),
+            p99_us: pct(0.99),
+            max_us: pct(1.0)
+        }
+    }
+}
+
+/// The results at one scale.
+#[derive(Serialize, Debug)]
+struct Run {
+    scale: u64,
+    sizes: Sizes,
+    /// Facts added to load the topology.
+    facts: usize,
+    load_secs: f64,
+    single: Latencies,
+    txn: Latencies,
+    /// `worker.step()` calls per single-fact update, from `stats`.
+    steps_per_update: f64,
+    /// `VmHWM` of the `ovn` process, where `/proc` has it.
+    peak_rss_kb: Option<u64>
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    ovn: String,
+    args: Vec<String>,
+    updates: usize,
+    runs: Vec<Run>
+}
+
+/// A running `ovn`.
+struct Ovn {
+    child: Child,
+    stdin: ChildStdin,
+    responses: json::StreamDeserializer<'static, json::de::IoRead<BufReader<ChildStdout>>, json::Value>
+}
+
+impl Ovn {
+    fn spawn(path: &str, args: &[String]) -> Result<Ovn, String> {
+        let mut child = Command::new(path).args(args).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ok(Ovn { child: child, stdin: stdin, responses: json::Deserializer::from_reader(stdout).into_iter() })
+    }
+
+    /// Sends `req`, and returns whether the answer was `ok` with its value.
+    fn request(&mut self, req: &json::Value) -> Result<(bool, json::Value), String> {
+        writeln!(self.stdin, "{}", req).map_err(|e| e.to_string())?;
+        self.stdin.flush().map_err(|e| e.to_string())?;
+        match self.responses.next() {
+            Some(Ok(resp)) => Ok((resp.get("ok").is_some(), resp)),
+            Some(Err(e))   => Err(format!("bad response: {}", e)),
+            None           => Err(format!("ovn exited"))
+        }
+    }
+
+    /// Sends a transaction, returning how many of its requests failed.
+    fn txn(&mut self, txn: &Txn) -> Result<usize, String> {
+        let mut errors = 0;
+        for r in [json::Value::from("start")].iter().chain(txn.iter()).chain([json::Value::from("commit")].iter()) {
+            if !self.request(r)?.0 { errors += 1 };
+        };
+        Ok(errors)
+    }
+
+    fn steps(&mut self) -> Result<u64, String> {
+        match self.request(&json::Value::from("stats"))? {
+            (true, resp) => resp["ok"]["steps"].as_u64().ok_or_else(|| format!("stats without steps: {}", resp)),
+            (false, resp) => Err(format!("stats: {}", resp))
+        }
+    }
+
+    fn peak_rss_kb(&self) -> Option<u64> {
+        let mut status = String::new();
+        File::open(format!("/proc/{}/status", self.child.id())).and_then(|mut f| f.read_to_string(&mut status)).ok()?;
+        status.lines().find(|l| l.starts_with("VmHWM:"))?.split_whitespace().nth(1)?.parse().ok()
+    }
+}
+
+impl Drop for Ovn {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn micros(t: Instant) -> f64 {
+    let d = t.elapsed();
+    d.as_secs() as f64 * 1e6 + d.subsec_nanos() as f64 * 1e-3
+}
+
+fn run(ovn: &str, args: &[String], sizes: Sizes, scale: u64, updates: usize) -> Result<Run, String> {
+    let mut p = Ovn::spawn(ovn, args)?;
+    let mut topo = Topology::new(sizes.clone());
+
+    let (started, mut facts) = (Instant::now(), 0);
+    for txn in topo.build() {
+        let errors = p.txn(&txn)?;
+        if errors > 0 { eprintln!("scale {}: {} requests of a topology transaction failed", scale, errors) };
+        facts += txn.len();
+    };
+    let load_secs = micros(started) * 1e-6;
+
+    let (mut us, mut errors, mut sent) = (Vec::new(), 0, 0);
+    let steps = p.steps()?;
+    for _ in 0..updates {
+        let txn = match topo.churn() { Some((_, t)) => t, None => break };
+        for r in &txn {
+            let t = Instant::now();
+            if !p.request(r)?.0 { errors += 1 };
+            us.push(micros(t));
+            sent += 1;
+        };
+    };
+    let steps_per_update = if sent == 0 { 0.0 } else { (p.steps()? - steps) as f64 / sent as f64 };
+    let single = Latencies::new(us, errors);
+
+    let (mut us, mut errors) = (Vec::new(), 0);
+    for _ in 0..updates {
+        let txn = match topo.churn() { Some((_, t)) => t, None => break };
+        let t = Instant::now();
+        errors += p.txn(&txn)?.min(1);
+        us.push(micros(t));
+    };
+    let txn = Latencies::new(us, errors);
+
+    Ok(Run {
+        scale: scale, sizes: sizes, facts: facts, load_secs: load_secs, single: single, txn: txn,
+        steps_per_update: steps_per_update, peak_rss_kb: p.peak_rss_kb()
+    })
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let ovn_args = match args.iter().position(|a| a == "--") {
+        Some(i) => { let rest = args.split_off(i + 1); args.pop(); rest },
+        None    => Vec::new()
+    };
+    let mut value = |name: &str| match args.iter().position(|a| a == name) {
+        Some(i) if i + 1 < args.len() => { let v = args.remove(i + 1); args.remove(i); Some(v) },
+        Some(_) => { eprintln!("{}: missing value", name); std::process::exit(-1) },
+        None    => None
+    };
+    let ovn = value("--ovn").unwrap_or_else(|| {
+        let mut path: PathBuf = std::env::current_exe().unwrap();
+        path.set_file_name("ovn");
+        path.to_string_lossy().into_owned()
+    });
+    let updates = value("--updates").map(|n| n.parse::<usize>().unwrap_or_else(|_| { eprintln!("--updates: {} is not a number", n); std::process::exit(-1) })).unwrap_or(200);
+    let scales: Vec<u64> = value("--scales").unwrap_or(String::from("1,2,4,8")).split(',').map(|k| {
+        k.parse().unwrap_or_else(|_| { eprintln!("--scales: {} is not a number", k); std::process::exit(-1) })
+    }).collect();
+    let sizes = match Sizes::from_args(&mut args) {
+        Ok(s) if args.is_empty() => s,
+        Ok(_)  => { eprintln!("unknown arguments: {}", args.join(" ")); std::process::exit(-1) },
+        Err(e) => { eprintln!("{}", e); std::process::exit(-1) }
+    };
+
+    let mut report = Report { ovn: ovn.clone(), args: ovn_args.clone(), updates: updates, runs: Vec::new() };
+    for &k in &scales {
+        let res = sizes.scaled(k).and_then(|s| run(&ovn, &ovn_args, s, k, updates));
+        match res {
+            Ok(r)  => {
+                eprintln!("scale {}: {} facts loaded in {:.2}s, single p50 {:.0}us, txn p50 {:.0}us",
+                          k, r.facts, r.load_secs, r.single.p50_us, r.txn.p50_us);
+                report.runs.push(r);
+            },
+            Err(e) => { eprintln!("scale {}: {}", k, e); std::process::exit(-1) }
+        };
+    };
+    json::to_writer_pretty(std::io::stdout(), &report).unwrap();
+    println!();
+}
@@ -0,0 +1,913 @@
+//! Compiles the rules in `ovn.dl` into `$OUT_DIR/ovn_rules.rs`, which `ovn.rs` includes: the
+//! `Fact` and `Relation` enums and `main`, with one input handle, one pair of shadow sets, one
+//! `History` and six `Request` arms per relation.
+//!
+//! Each rule becomes a chain of differential operators over tuples of the variables still
+//! needed: atoms are `join_map`s keyed on the variables they share with what came before,
+//! negated atoms `antijoin`s, conditions `filter`s and assignments `map`s. Constructor
+//! patterns in atoms turn into a `filter` followed by a `match`. Relations are sharded across
+//! workers by key, and so are the shadow sets of derived relations: worker 0 reads the
+//! requests, keeps the shadow sets of input relations, which only it writes, and gathers a
+//! derived relation's shards from every worker when a request needs them. Joins, antijoins
+//! and each relation's `distinct` keep their traces in `compact::Traces`, which is compacted
+//! as epochs advance.
+
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+mod dl;
+
+use dl::{Atom, Expr, Literal, Program, Relation, Rule};
+
+fn main() {
+    println!("cargo:rerun-if-changed=ovn.dl");
+    println!("cargo:rerun-if-changed=dl.rs");
+    println!("cargo:rerun-if-changed=ipam.rs");
+
+    let rules = read("ovn.dl");
+    let prog = match Program::parse(&rules) {
+        Ok(p)  => p,
+        Err(e) => panic!("ovn.dl:{}", e)
+    };
+    let code = generate(&prog, &read("ipam.rs"));
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("ovn_rules.rs");
+    File::create(&out).and_then(|mut f| f.write_all(code.as_bytes()))
+        .unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
+
+/// Derived relations whose changes give `ipam!` something to do: see `ipam.rs`.
+const IPAM_WATCHED: &[&str] = &["LPortDynIPPending", "LPortDynIPStale", "LSwitchIPInUse",
+                                "LPortDynMACPending", "LPortDynMACStale", "MACInUse"];
+
+/// Input relations that `ipam!` writes, which clients may write only as they may write derived
+/// relations.
+const IPAM_ALLOCATED: &[&str] = &["LPortDynIPAlloc", "LPortDynMACAlloc"];
+
+fn read(path: &str) -> String {
+    let mut s = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut s))
+        .unwrap_or_else(|e| panic!("{}: {}", path, e));
+    s
+}
+
+/// The `serde(with)` module in `ovn.rs` of a column type, if it is a bare address: a `u32` or
+/// `u64` to the rules, with a text form in the JSON protocol.
+fn bare_addr_with(ty: &str) -> Option<&'static str> {
+    match ty {
+        "ip4_addr_t" => Some("ip4_addr_serde"),
+        "mac_addr_t" => Some("mac_addr_serde"),
+        _            => None
+    }
+}
+
+/// Element type of a relation's collection: a tuple of its columns, or the column itself.
+fn tuple_type(r: &Relation) -> String {
+    let tys: Vec<&str> = r.columns.iter().map(|c| c.ty.as_str()).collect();
+    if tys.len() == 1 { tys[0].to_string() } else { format!("({})", tys.join(", ")) }
+}
+
+/// `(a,b)`, `a` or `()`: variables moved into a tuple, or a by-value pattern binding them.
+fn tup(vs: &[String]) -> String {
+    if vs.len() == 1 { vs[0].clone() } else { format!("({})", vs.join(",")) }
+}
+
+/// The same tuple built from borrowed variables.
+fn tup_cloned(vs: &[String]) -> String {
+    let cs: Vec<String> = vs.iter().map(|v| format!("{}.clone()", v)).collect();
+    if cs.len() == 1 { cs[0].clone() } else { format!("({})", cs.join(",")) }
+}
+
+/// A closure argument binding the variables of a borrowed tuple.
+fn tup_ref(vs: &[String]) -> String {
+    if vs.len() == 1 {
+        vs[0].clone()
+    } else {
+        let rs: Vec<String> = vs.iter().map(|v| format!("ref {}", v)).collect();
+        format!("&({})", rs.join(","))
+    }
+}
+
+fn expr(e: &Expr) -> String {
+    match *e {
+        Expr::Var(ref v)                 => format!("{}.clone()", v),
+        Expr::Int(ref i)                 => i.clone(),
+        Expr::Bool(b)                    => b.to_string(),
+        Expr::Str(ref s)                 => format!("String::from(\"{}\")", s),
+        Expr::Bytes(ref s)               => format!("b\"{}\"", s),
+        Expr::Wild                       => "_".to_string(),
+        Expr::Cons(ref p, ref fs, _)     => {
+            let fs: Vec<String> = fs.iter().map(|&(ref n, ref e)| format!("{}: {}", n, expr(e))).collect();
+            format!("{}{{{}}}", p, fs.join(", "))
+        },
+        Expr::Call(ref p, ref args)      => format!("{}({})", p, exprs(args)),
+        Expr::Method(ref r, ref m, ref args) => format!("{}.{}({})", expr(r), m, exprs(args)),
+        Expr::Unop(op, ref e)            => format!("({}{})", op, expr(e)),
+        Expr::Binop(op, ref l, ref r)    => format!("({} {} {})", expr(l), op, expr(r)),
+    }
+}
+
+fn exprs(es: &[Expr]) -> String {
+    es.iter().map(expr).collect::<Vec<_>>().join(", ")
+}
+
+/// Translates atom arguments into a tuple pattern. Repeated variables and literals become
+/// fresh variables compared in `guards`; with `refs` everything binds by reference (for
+/// `filter`), otherwise only the `wanted` variables bind, by value (for `map`).
+struct Pattern<'a> {
+    refs: bool,
+    wanted: &'a [String],
+    seen: Vec<String>,
+    guards: Vec<String>,
+    fresh: usize,
+    refutable: bool,
+}
+
+impl<'a> Pattern<'a> {
+    fn new(refs: bool, wanted: &'a [String]) -> Self {
+        Pattern { refs: refs, wanted: wanted, seen: Vec::new(), guards: Vec::new(), fresh: 0, refutable: false }
+    }
+
+    fn tuple(&mut self, args: &[Expr]) -> String {
+        let ps: Vec<String> = args.iter().map(|a| self.pat(a)).collect();
+        if ps.len() == 1 { ps[0].clone() } else { format!("({})", ps.join(",")) }
+    }
+
+    fn bind(&self, v: &str) -> String {
+        if self.refs { format!("ref {}", v) } else if self.wanted.iter().any(|w| w == v) { v.to_string() } else { "_".to_string() }
+    }
+
+    fn pat(&mut self, e: &Expr) -> String {
+        match *e {
+            Expr::Wild => "_".to_string(),
+            Expr::Var(ref v) => {
+                if self.seen.contains(v) {
+                    let d = format!("__dup{}", self.fresh);
+                    self.fresh += 1;
+                    self.refutable = true;
+                    self.guards.push(format!("*{} == *{}", v, d));
+                    if self.refs { format!("ref {}", d) } else { "_".to_string() }
+                } else {
+                    self.seen.push(v.clone());
+                    self.bind(v)
+                }
+            },
+            Expr::Cons(ref p, ref fs, rest) => {
+                self.refutable = true;
+                let mut ps: Vec<String> = fs.iter().map(|&(ref n, ref e)| format!("{}: {}", n, self.pat(e))).collect();
+                if rest { ps.push("..".to_string()) };
+                format!("{}{{{}}}", p, ps.join(", "))
+            },
+            _ => {
+                let l = format!("__lit{}", self.fresh);
+                self.fresh += 1;
+                self.refutable = true;
+                self.guards.push(format!("*{} == {}", l, e));
+                if self.refs { format!("ref {}", l) } else { "_".to_string() }
+            }
+        }
+    }
+}
+
+/// A closure taking `args` to `body`.
+fn closure(args: &str, body: &str) -> String {
+    format!("|{}| {{ {} }}", args, body)
+}
+
+/// `Rel`, filtered to tuples matching the atom and mapped to `out`, an expression over the
+/// variables in `wanted`.
+fn scan(a: &Atom, wanted: &[String], out: &str) -> String {
+    let mut fp = Pattern::new(true, wanted);
+    let fpat = fp.tuple(&a.args);
+    let mut mp = Pattern::new(false, wanted);
+    let mpat = mp.tuple(&a.args);
+    if fp.refutable {
+        let guard = if fp.guards.is_empty() { String::new() } else { format!(" if {}", fp.guards.join(" && ")) };
+        format!("{}.filter({}).map({})", a.relation,
+                closure("_x_", &format!("match *_x_ {{{}{} => true, _ => false}}", fpat, guard)),
+                closure("_x_", &format!("match _x_ {{{} => {}, _ => unreachable!()}}", mpat, out)))
+    } else {
+        format!("{}.map({})", a.relation, closure("_x_", &format!("match _x_ {{{} => {}}}", mpat, out)))
+    }
+}
+
+fn keep(vs: &[String], needed: &[String]) -> Vec<String> {
+    vs.iter().filter(|v| needed.contains(v)).cloned().collect()
+}
+
+/// The operator chain computing one rule's head tuples.
+fn rule_chain(rule: &Rule) -> String {
+    let body = &rule.body;
+    let needed = rule.needed();
+
+    let mut ops = Vec::new();
+    let mut state = match body[0] {
+        Literal::Atom(ref a) => {
+            let mut vs = Vec::new();
+            a.vars(&mut vs);
+            let next = keep(&vs, &needed[0]);
+            ops.push(scan(a, &next, &tup(&next)));
+            next
+        },
+        _ => unreachable!()
+    };
+    for (i, lit) in body.iter().enumerate().skip(1) {
+        let next = match *lit {
+            Literal::Atom(ref a) => {
+                let mut vs = Vec::new();
+                a.vars(&mut vs);
+                let key: Vec<String> = vs.iter().filter(|v| state.contains(v)).cloned().collect();
+                let new: Vec<String> = vs.iter().filter(|v| !state.contains(v) && needed[i].contains(v)).cloned().collect();
+                let rest: Vec<String> = state.iter().filter(|v| !key.contains(v) && needed[i].contains(v)).cloned().collect();
+                let mut wanted = key.clone();
+                wanted.extend(new.iter().cloned());
+                let mut next = keep(&state, &needed[i]);
+                next.extend(new.iter().cloned());
+                ops.push(format!(".map({})", closure(&tup(&state), &format!("({},{})", tup(&key), tup(&rest)))));
+                ops.push(format!(".join_map_held(&traces1, &({}), {})",
+                                 scan(a, &wanted, &format!("({},{})", tup(&key), tup(&new))),
+                                 closure(&format!("{}, {}, {}", tup_ref(&key), tup_ref(&rest), tup_ref(&new)), &tup_cloned(&next))));
+                next
+            },
+            Literal::Not(ref a) => {
+                let mut key = Vec::new();
+                a.vars(&mut key);
+                let rest: Vec<String> = state.iter().filter(|v| !key.contains(v) && needed[i].contains(v)).cloned().collect();
+                let next = keep(&state, &needed[i]);
+                ops.push(format!(".map({})", closure(&tup(&state), &format!("({},{})", tup(&key), tup(&rest)))));
+                ops.push(format!(".antijoin_held(&traces1, &({}).distinct_held(&traces1))", scan(a, &key, &tup(&key))));
+                ops.push(format!(".map({})", closure(&format!("({},{})", tup(&key), tup(&rest)), &tup(&next))));
+                next
+            },
+            Literal::Let(ref v, ref e) => {
+                let mut next = keep(&state, &needed[i]);
+                if needed[i].contains(v) { next.push(v.clone()) };
+                ops.push(format!(".map({})", closure(&tup(&state), &format!("let {} = {}; {}", v, expr(e), tup(&next)))));
+                next
+            },
+            Literal::Filter(ref e) => {
+                ops.push(format!(".filter({})", closure(&tup_ref(&state), &expr(e))));
+                let next = keep(&state, &needed[i]);
+                if next != state {
+                    ops.push(format!(".map({})", closure(&tup(&state), &tup(&next))));
+                };
+                next
+            }
+        };
+        state = next;
+    };
+    let head: Vec<String> = rule.head.args.iter().map(expr).collect();
+    let head = if head.len() == 1 { head[0].clone() } else { format!("({})", head.join(",")) };
+    ops.push(format!(".map({})", closure(&tup(&state), &head)));
+    ops.join("\n                  ")
+}
+
+fn generate(prog: &Program, ipam: &str) -> String {
+    let rels = prog.enum_order();
+    let order = match prog.dataflow_order() {
+        Ok(o)  => o,
+        Err(e) => panic!("ovn.dl: {}", e)
+    };
+    let inputs: Vec<&Relation> = rels.iter().cloned().filter(|r| r.kind.is_input()).collect();
+    let derived: Vec<&Relation> = rels.iter().cloned().filter(|r| !r.kind.is_input()).collect();
+    let mut s = String::new();
+
+    let ns = prog.namespace.as_ref().map_or("u32", |c| c.ty.as_str());
+    s.push_str(&format!("/// The type of the namespace column.\ntype Namespace = {};\n\n", ns));
+    s.push_str("#[derive(Eq, Hash, PartialEq, Clone, Serialize, Deserialize, Debug)]\nenum Fact {\n");
+    for r in &rels {
+        let tys: Vec<String> = r.columns.iter().map(|c| match bare_addr_with(&c.ty) {
+            Some(m) => format!("#[serde(with = \"{}\")] {}", m, c.ty),
+            None    => c.ty.clone()
+        }).collect();
+        s.push_str(&format!("    {}({}),\n", r.name, tys.join(", ")));
+    };
+    s.push_str("}\n\n#[derive(Serialize, Deserialize, Debug)]\nenum Relation {\n");
+    for r in &rels {
+        s.push_str(&format!("    {},\n", r.name));
+    };
+    s.push_str(&format!("}}\n\n/// The input relations that the address allocator writes.\nconst IPAM_ALLOCATED: &[&str] = &{:?};\n", IPAM_ALLOCATED));
+    s.push_str("\nimpl Fact {\n    /// The relation of the fact, if it is derived or written by the address allocator.\n");
+    s.push_str("    fn derived(&self) -> Option<&'static str> {\n        match *self {\n");
+    for r in rels.iter().filter(|r| !r.kind.is_input() || IPAM_ALLOCATED.contains(&r.name.as_str())) {
+        s.push_str(&format!("            Fact::{0}(..) => Some(\"{0}\"),\n", r.name));
+    };
+    s.push_str("            _ => None\n        }\n    }\n\n");
+    s.push_str("    /// The namespace of the fact, unless its relation is global.\n");
+    s.push_str("    fn namespace(&self) -> Option<&Namespace> {\n        match *self {\n");
+    for r in rels.iter().filter(|r| r.namespaced) {
+        s.push_str(&format!("            Fact::{}(ref ns, ..) => Some(ns),\n", r.name));
+    };
+    s.push_str("            _ => None\n        }\n    }\n\n");
+    s.push_str("    /// Checks the column values of the fact and the `check` conditions of its relation.\n");
+    s.push_str("    fn validate(&self) -> Result<(), String> {\n        match *self {\n");
+    for r in &rels {
+        let cols: Vec<String> = r.columns.iter().map(|c| format!("ref {}", c.name)).collect();
+        s.push_str(&format!("            Fact::{}({}) => {{\n", r.name, cols.join(", ")));
+        for c in &r.columns {
+            s.push_str(&format!("                Valid::valid({1}).map_err(|e| format!(\"{0}.{1}: {{}}\", e))?;\n", r.name, c.name));
+        };
+        for &(ref cond, ref msg) in &r.checks {
+            s.push_str(&format!("                if !{} {{ return Err(String::from({:?})) }};\n", expr(cond), format!("{}: {}", r.name, msg)));
+        };
+        s.push_str("                Ok(())\n            },\n");
+    };
+    s.push_str("        }\n    }\n}\n");
+
+    s.push_str(r#"
+fn main() {
+
+    // print addresses in their text form, and accept writes to derived relations; timely must
+    // not see the flags
+    TEXT_ADDRS.store(std::env::args().any(|a| a == "--text-addrs"), Ordering::Relaxed);
+    DERIVED_WRITES.store(std::env::args().any(|a| a == "--allow-derived-writes"), Ordering::Relaxed);
+    let mut args: Vec<String> = std::env::args().filter(|a| a != "--text-addrs" && a != "--allow-derived-writes").collect();
+
+    // `--history EPOCHS` keeps the changes of the last EPOCHS epochs for `enm_at` and `diff`
+    if let Some(i) = args.iter().position(|a| a == "--history") {
+        match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) => HISTORY.store(n, Ordering::Relaxed),
+            None    => {
+                eprintln!("--history: expected a number of epochs");
+                std::process::exit(-1);
+            }
+        };
+        args.drain(i..i + 2);
+    };
+
+    // `--audit COMMITS` keeps the last COMMITS committed changes for `audit`; 0 disables it
+    if let Some(i) = args.iter().position(|a| a == "--audit") {
+        match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) => AUDIT.store(n, Ordering::Relaxed),
+            None    => {
+                eprintln!("--audit: expected a number of commits");
+                std::process::exit(-1);
+            }
+        };
+        args.drain(i..i + 2);
+    };
+
+    // `--follow ADDR` and `--replicate ADDR`: see `replica.rs`
+    let config = match replica::Config::from_args(&mut args) {
+        Ok(c)  => c,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(-1);
+        }
+    };
+
+    // `--rules FILE` interprets the rules in FILE instead of running the compiled ones
+    if let Some(i) = args.iter().position(|a| a == "--rules") {
+        if i + 1 == args.len() {
+            eprintln!("--rules: missing file name");
+            std::process::exit(-1);
+        };
+        let path = args.remove(i + 1);
+        args.remove(i);
+        return interp::main(path, args, config);
+    };
+
+    // start up timely computation: `-w` threads per process, and `-n`, `-p` and `-h hostfile`
+    // for a cluster of processes, whose process 0 reads the requests
+    execute_from_args!(args.into_iter(), move |worker| {
+        let probe = probe::Handle::new();
+        let mut probe1 = probe.clone();
+        let traces = compact::Traces::new();
+        let traces1 = traces.clone();
+
+        let mut xaction : bool = false;
+
+"#);
+    for r in &rels {
+        let t = tuple_type(r);
+        s.push_str(&format!("        let mut _r{0}: Rc<RefCell<HashSet<{1}>>> = Rc::new(RefCell::new(HashSet::new()));\n", r.name, t));
+        s.push_str(&format!("        let mut _w{0}: Rc<RefCell<HashSet<{1}>>> = _r{0}.clone();\n", r.name, t));
+        s.push_str(&format!("        let mut _h{0}: Rc<RefCell<History<{1}>>> = Rc::new(RefCell::new(History::new()));\n", r.name, t));
+        s.push_str(&format!("        let mut _wh{0}: Rc<RefCell<History<{1}>>> = _h{0}.clone();\n", r.name, t));
+        if !r.kind.is_input() {
+            s.push_str(&format!("        let _g{0} = _r{0}.clone();\n        let _gh{0} = _h{0}.clone();\n", r.name));
+        };
+    };
+    for r in &inputs {
+        let t = tuple_type(r);
+        s.push_str(&format!("        let mut __rDelta{0}: Rc<RefCell<HashMap<{1}, i8>>> = Rc::new(RefCell::new(HashMap::new()));\n", r.name, t));
+        s.push_str(&format!("        let mut __wDelta{0}: Rc<RefCell<HashMap<{1}, i8>>> = __rDelta{0}.clone();\n", r.name, t));
+    };
+    s.push_str("        let ipam_dirty = Rc::new(Cell::new(false));\n");
+    for name in IPAM_WATCHED {
+        s.push_str(&format!("        let _d{0} = ipam_dirty.clone();\n", name));
+    };
+
+    let handles: Vec<String> = rels.iter().map(|r| format!("_{}", r.name)).collect();
+    let muts: Vec<String> = handles.iter().map(|h| format!("mut {}", h)).collect();
+    s.push_str(&format!("\n        let ({}) = worker.dataflow::<u64,_,_>(move |outer| {{\n", muts.join(",")));
+    for r in &order {
+        s.push_str(&format!("            let (mut _{0}, {0}) = outer.new_collection::<{1},isize>();\n", r.name, tuple_type(r)));
+        if r.kind.is_input() {
+            s.push_str(&format!("            {0}.inner.inspect(move |x| {{ xupd(&_w{0}, &__wDelta{0}, &(x.0), x.2); _wh{0}.borrow_mut().record(&(x.0), x.1, x.2) }}).probe_with(&mut probe1);\n", r.name));
+        };
+        for (i, rule) in prog.rules_for(&r.name).iter().enumerate() {
+            let name = format!("{}#{} (line {})", r.name, i + 1, rule.line);
+            s.push_str(&format!("            let {0} = {0}.concat(&profile::named(&({1}), {2:?}));\n", r.name, rule_chain(rule), name));
+        };
+        s.push_str(&format!("            let {0} = profile::named(&{0}.distinct_held(&traces1), \"{0}\");\n", r.name));
+    };
+    for r in &derived {
+        let dirty = if IPAM_WATCHED.contains(&r.name.as_str()) { format!("; _d{}.set(true)", r.name) } else { String::new() };
+        s.push_str(&format!("            {0}.inner.inspect(move |x| {{ upd(&_w{0}, &(x.0), x.2); _wh{0}.borrow_mut().record(&(x.0), x.1, x.2){1} }}).probe_with(&mut probe1);\n", r.name, dirty));
+    };
+    s.push_str(&format!("            ({})\n        }});\n", handles.join(",")));
+    s.push_str(r#"
+        // the shadow state of a derived relation stays with the worker that derived its facts;
+        // `gather!` asks every worker for its share, with its history if the query says so, or
+        // for whether a relation `ipam!` watches changed there
+        let gathered = Rc::new(RefCell::new(Vec::new()));
+        let gathered1 = gathered.clone();
+        let gprobe = probe::Handle::new();
+        let mut gprobe1 = gprobe.clone();
+        let mut _gather = worker.dataflow::<u64,_,_>(move |outer| {
+            let (handle, queries) = outer.new_collection::<(usize, bool),isize>();
+            queries.inner.broadcast().unary_stream(timely::dataflow::channels::pact::Pipeline, "Gather", move |input, output| {
+                input.for_each(|time, data| {
+                    let mut session = output.session(&time);
+                    for &((rel, history), _, _) in data.iter() {
+                        session.give(match rel {
+"#);
+    for (i, r) in derived.iter().enumerate() {
+        s.push_str(&format!("                            {1} => shard(&_g{0}.borrow(), &_gh{0}.borrow(), history),\n", r.name, i));
+    };
+    s.push_str(&format!("                            {} => serde_json::to_string(&ipam_dirty.replace(false)).unwrap(),\n", derived.len()));
+    s.push_str(r#"                            _ => unreachable!()
+                        });
+                    };
+                });
+            }).exchange(|_| 0).inspect(move |x| gathered1.borrow_mut().push(x.clone())).probe_with(&mut gprobe1);
+            handle
+        });
+"#);
+    s.push_str(r#"
+        // worker 0 serves the protocol; the others drop their input handles so as not to hold back
+        // the frontier, and step until worker 0 drops its own
+        if worker.index() != 0 {
+"#);
+    for h in &handles {
+        s.push_str(&format!("            drop({});\n", h));
+    };
+    s.push_str("            drop(_gather);\n");
+    s.push_str(r#"            while worker.step() {
+                if let Some(e) = probe.with_frontier(|f| f.iter().cloned().min()) { traces.compact(e) };
+            };
+            return;
+        };
+"#);
+
+    s.push_str(r#"
+        let mut epoch = 0;
+        let mut queries = 0;
+        let mut steps: u64 = 0;
+        let mut started = Instant::now();
+        let mut commits = VecDeque::new();
+        let mut audit = Audit::new();
+        let mut ip_next = HashMap::new();
+        let mut mac_next = HashMap::new();
+        let mut txn = (TxnMeta::default(), 0);
+        let mut following = config.follow.is_some();
+        let mut followers = replica::Followers::new();
+        let events = match replica::events::<Request, Vec<(Fact, i8)>>(config.follow.clone(), config.replicate.clone()) {
+            Ok(ev) => ev,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(-1);
+            }
+        };
+
+        for ev in events {
+            macro_rules! advance {
+                () => {{
+"#);
+    for h in &handles {
+        s.push_str(&format!("                    {}.advance_to(epoch);\n", h));
+    };
+    for h in &handles {
+        s.push_str(&format!("                    {}.flush();\n", h));
+    };
+    s.push_str("                    traces.compact(epoch);\n");
+    s.push_str(r#"                }}
+            }
+
+            macro_rules! insert {
+                ($rel:ident, $set:ident, $args:expr) => {{
+                    let v = $args;
+                    if !$set.borrow().contains(&v) {
+                        $rel.insert(v);
+                        epoch = epoch+1;
+                        advance!();
+                        while probe.less_than($rel.time()) {
+                            worker.step();
+                            steps += 1;
+                        };
+                    };
+                }}
+            }
+
+            macro_rules! insert_resp {
+                ($rel:ident, $set:ident, $args:expr) => {{
+                    let from = epoch;
+                    insert!($rel, $set, $args);
+                    ipam!();
+                    if !xaction { ship!(from) };
+                    let resp: Response<()> = Response::ok(());
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+
+            macro_rules! remove {
+                ($rel:ident, $set:ident, $args:expr) => {{
+                    let v = $args;
+                    if $set.borrow().contains(&v) {
+                        $rel.remove(v);
+                        epoch = epoch+1;
+                        advance!();
+                        while probe.less_than($rel.time()) {
+                            worker.step();
+                            steps += 1;
+                        };
+                    };
+                }}
+            }
+
+            // the shards that every worker holds for query `$q`: see `_gather`
+            macro_rules! gather {
+                ($q:expr) => {{
+                    _gather.insert($q);
+                    queries += 1;
+                    _gather.advance_to(queries);
+                    _gather.flush();
+                    while gprobe.less_than(_gather.time()) {
+                        worker.step();
+                    };
+                    gathered.borrow_mut().drain(..).collect::<Vec<String>>()
+                }}
+            }
+
+            // the facts of a derived relation, and its history if `$history`
+            macro_rules! gathered {
+"#);
+    for (i, r) in derived.iter().enumerate() {
+        s.push_str(&format!("                ({0}, $history:expr) => {{ merge_shards::<{1}>(&gather!(({2}, $history))) }};\n", r.name, tuple_type(r), i));
+    };
+    s.push_str(&format!(r#"            }}
+
+            // whether a relation in `IPAM_WATCHED` changed since the last time
+            macro_rules! ipam_dirty {{
+                () => {{{{ gather!(({}, false)).iter().any(|d| d == "true") }}}}
+            }}
+
+"#, derived.len()));
+    for line in ipam.lines() {
+        if line.is_empty() { s.push('\n') } else { s.push_str(&format!("            {}\n", line)) };
+    };
+    s.push_str(r#"
+            macro_rules! remove_resp {
+                ($rel:ident, $set:ident, $args:expr) => {{
+                    let from = epoch;
+                    remove!($rel, $set, $args);
+                    ipam!();
+                    if !xaction { ship!(from) };
+                    let resp: Response<()> = Response::ok(());
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+
+            macro_rules! check {
+                ($set:expr) => {{
+                    let resp = Response::ok(!$set.borrow().is_empty());
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+
+            macro_rules! enm {
+                ($set:expr, $tys:expr) => {{
+                    let mut rows = serde_json::to_value(&*$set.borrow()).unwrap();
+                    text_rows(&mut rows, $tys);
+                    let resp = Response::ok(rows);
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+            macro_rules! start {
+                ($meta:expr) => {{
+                    let resp = if xaction {
+                                   Response::err(format!("transaction already in progress"))
+                               } else {
+                                   delta_cleanup!();
+                                   xaction = true;
+                                   started = Instant::now();
+                                   txn = ($meta, epoch);
+                                   Response::ok(())
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+
+            macro_rules! scoped {
+                ($set:expr) => {{
+                    match scope {
+                        Some(ref ns) => Rc::new(RefCell::new($set.borrow().iter().filter(|x| x.0 == *ns).cloned().collect::<HashSet<_>>())),
+                        None         => $set.clone()
+                    }
+                }}
+            }
+
+            macro_rules! enm_at {
+                ($set:expr, $hist:expr, $e:expr, $tys:expr) => {{
+                    let resp = match $hist.borrow().at(&$set.borrow(), $e, epoch) {
+                                   Ok(set) => {
+                                       let mut rows = serde_json::to_value(&set).unwrap();
+                                       text_rows(&mut rows, $tys);
+                                       Response::ok(rows)
+                                   },
+                                   Err(e)  => Response::err(e)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+
+            macro_rules! diff {
+                ($set:expr, $hist:expr, $from:expr, $to:expr, $tys:expr) => {{
+                    let resp = match $hist.borrow().diff(&$set.borrow(), $from, $to, epoch) {
+                                   Ok(d)  => {
+                                       let mut d = serde_json::to_value(&d).unwrap();
+                                       text_rows(&mut d["added"], $tys);
+                                       text_rows(&mut d["removed"], $tys);
+                                       Response::ok(d)
+                                   },
+                                   Err(e) => Response::err(e)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                }}
+            }
+            macro_rules! delta {
+                ($delta: expr) => {{
+"#);
+    for r in &inputs {
+        let args: Vec<String> = (1..r.columns.len() + 1).map(|i| format!("a{}", i)).collect();
+        s.push_str(&format!("                    let d = __rDelta{}.borrow();\n", r.name));
+        s.push_str(&format!("                    for ({},v) in d.iter().filter(|&(_, v)| *v != 0) {{\n", tup_ref(&args)));
+        s.push_str(&format!("                        $delta.insert((Fact::{}({}),v.clone()));\n", r.name,
+                            args.iter().map(|a| format!("{}.clone()", a)).collect::<Vec<_>>().join(", ")));
+        s.push_str("                    };\n");
+    };
+    s.push_str("                }}\n            }\n            macro_rules! delta_cleanup {\n                () => {{\n");
+    for r in &inputs {
+        s.push_str(&format!("                    __rDelta{}.borrow_mut().clear();\n", r.name));
+    };
+    s.push_str("                }}\n            }\n            macro_rules! delta_undo {\n                () => {{\n");
+    for r in &inputs {
+        s.push_str(&format!(r#"                    let mut d = __rDelta{0}.borrow().clone();
+                    for (k,v) in d.drain() {{
+                        if v == 1 {{
+                            remove!(_{0}, _r{0}, k);
+                        }} else if v == -1 {{
+                            insert!(_{0}, _r{0}, k);
+                        }};
+                    }};
+"#, r.name));
+    };
+    s.push_str("               }}\n            }\n            macro_rules! snapshot {\n                () => {{\n                    let mut facts = Vec::new();\n");
+    for r in &inputs {
+        let args: Vec<String> = (1..r.columns.len() + 1).map(|i| format!("a{}", i)).collect();
+        let fact = format!("Fact::{}({})", r.name, args.iter().map(|a| format!("{}.clone()", a)).collect::<Vec<_>>().join(", "));
+        s.push_str(&format!("                    let d = __rDelta{}.borrow();\n", r.name));
+        s.push_str(&format!("                    for x in _r{}.borrow().iter().filter(|x| d.get(*x) != Some(&1)) {{\n", r.name));
+        s.push_str(&format!("                        let {} = x;\n                        facts.push(({}, 1i8));\n                    }};\n", tup_ref(&args), fact));
+        s.push_str(&format!("                    for ({},_) in d.iter().filter(|&(_, v)| *v == -1) {{\n", tup_ref(&args)));
+        s.push_str(&format!("                        facts.push(({}, 1i8));\n                    }};\n", fact));
+    };
+    s.push_str("                    facts\n                }}\n            }\n            macro_rules! apply {\n                ($changes:expr) => {{\n");
+    s.push_str("                    for (f, w) in $changes {\n                        match f {\n");
+    for r in &inputs {
+        let args: Vec<String> = (0..r.columns.len()).map(|i| format!("a{}", i)).collect();
+        s.push_str(&format!("                            Fact::{0}({1}) => if w > 0 {{ insert!(_{0}, _r{0}, {2}) }} else {{ remove!(_{0}, _r{0}, {2}) }},\n",
+                            r.name, args.join(","), tup(&args)));
+    };
+    s.push_str(r#"                            f => eprintln!("ignoring replicated change to derived fact {:?}", f)
+                        };
+                    };
+                    delta_cleanup!();
+                }}
+            }
+
+            // sends the changes made since epoch `$from`, outside of a transaction, to the
+            // followers and records them in the audit log
+            macro_rules! ship {
+                ($from:expr) => {{
+                    let mut delta = HashSet::new();
+                    delta!(delta);
+                    delta_cleanup!();
+                    followers.send(epoch, &delta);
+                    if !delta.is_empty() { audit.record(TxnMeta::default(), ($from, epoch), delta.into_iter().collect()) };
+                }}
+            }
+
+            // replaces the input facts with those of `$facts`, a snapshot of the primary's
+            macro_rules! resync {
+                ($facts:expr) => {{
+                    let facts: HashSet<(Fact, i8)> = $facts.into_iter().collect();
+                    let stale: Vec<(Fact, i8)> = snapshot!().into_iter().filter(|f| !facts.contains(f)).map(|(f, _)| (f, -1)).collect();
+                    apply!(stale);
+                    apply!(facts);
+                }}
+            }
+
+            let val = match ev {
+                replica::Event::Request(r)      => r,
+                replica::Event::Follower(s)     => {
+                    if following { eprintln!("turning away a follower: not a primary until promoted") } else { followers.add(s, &snapshot!()) };
+                    continue
+                },
+                replica::Event::Snapshot(f)     => {
+                    if following { resync!(f) };
+                    continue
+                },
+                replica::Event::Changes(Ok(c))  => {
+                    if following { apply!(c) };
+                    continue
+                },
+                replica::Event::Changes(Err(e)) => {
+                    if following { eprintln!("{}", e) };
+                    continue
+                }
+            };
+            let req = match val {
+                            Ok(r)  => r,
+                            Err(e) => {
+                                let resp: Response<()> = Response::err(e);
+                                serde_json::to_writer(stdout(), &resp).unwrap();
+                                stdout().flush().unwrap();
+                                continue;
+                            }
+                        };
+            let (scope, req) = match req {
+                Request::scoped(ns, r) => (Some(ns), *r),
+                r                      => (None, r)
+            };
+            if let Some(ref ns) = scope {
+                if let Err(e) = in_namespace(ns, &req) {
+                    let resp: Response<()> = Response::err(e);
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                    continue;
+                };
+            };
+            if following {
+                if let Err(e) = read_only(&req) {
+                    let resp: Response<()> = Response::err(e);
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                    continue;
+                };
+            };
+            let derived = match req {
+                Request::add(ref f) | Request::del(ref f) => f.derived(),
+                _ => None
+            };
+            if let Some(rel) = derived {
+                if let Err(e) = derived_write(rel, &format!("{:?}", req)) {
+                    let resp: Response<()> = Response::err(e);
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                    continue;
+                };
+            };
+            if let Request::add(ref f) = req {
+                if let Err(e) = f.validate() {
+                    let resp: Response<()> = Response::err(e);
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                    continue;
+                };
+            };
+
+            match req {
+                Request::start                       => start!(TxnMeta::default()),
+                Request::start_with(meta)            => start!(meta),
+                Request::rollback                    => {
+                    let resp = if !xaction {
+                                   Response::err(format!("no transaction in progress"))
+                               } else {
+                                   delta_undo!();
+                                   delta_cleanup!();
+                                   xaction = false;
+                                   Response::ok(())
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::schema                      => {
+                    let resp = match dl::Program::parse(RULES) {
+                                   Ok(prog) => Response::ok(schema(&prog)),
+                                   Err(e)   => Response::err(e)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::commit                      => {
+                    let resp = if !xaction {
+                                   Response::err(format!("no transaction in progress"))
+                               } else {
+                                   let mut delta = HashSet::new();
+                                   delta!(delta);
+                                   delta_cleanup!();
+                                   followers.send(epoch, &delta);
+                                   xaction = false;
+                                   commit_stats(&mut commits, epoch, started);
+                                   audit.record(txn.0.clone(), (txn.1, epoch), delta.iter().cloned().collect());
+                                   Response::ok(delta)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::scoped(..)                  => unreachable!(),
+                Request::promote                     => {
+                    let resp = if !following {
+                                   Response::err(format!("not a follower"))
+                               } else {
+                                   following = false;
+                                   Response::ok(())
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::audit(fact)                 => {
+                    let resp = match audit.query(fact.as_ref()) {
+                                   Ok(entries) => Response::ok(entries),
+                                   Err(e)      => Response::err(e)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::stats                       => {
+                    let mut rows = BTreeMap::new();
+                    let mut deltas = BTreeMap::new();
+"#);
+    for r in &inputs {
+        s.push_str(&format!("                    rows.insert(String::from(\"{0}\"), _r{0}.borrow().len());\n", r.name));
+    };
+    for r in &derived {
+        s.push_str(&format!("                    rows.insert(String::from(\"{0}\"), gathered!({0}, false).0.len());\n", r.name));
+    };
+    for r in &inputs {
+        s.push_str(&format!("                    deltas.insert(String::from(\"{0}\"), __rDelta{0}.borrow().len());\n", r.name));
+    };
+    s.push_str(r#"                    let resp = Response::ok(Stats {
+                                   epoch: epoch,
+                                   steps: steps,
+                                   commits: commits.iter().cloned().collect(),
+                                   rows: rows,
+                                   deltas: deltas
+                               });
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+                Request::profile                     => {
+                    let resp = match profile::report() {
+                                   Ok(p)  => Response::ok(p),
+                                   Err(e) => Response::err(e)
+                               };
+                    serde_json::to_writer(stdout(), &resp).unwrap();
+                    stdout().flush().unwrap();
+                },
+"#);
+    for r in &rels {
+        // the front end holds the facts of input relations, and gathers those of derived ones
+        let bind = |history: bool| if r.kind.is_input() { String::new() } else {
+            format!("let (_r{0}, _h{0}) = gathered!({0}, {1}); let (_r{0}, _h{0}) = (Rc::new(RefCell::new(_r{0})), RefCell::new(_h{0})); ",
+                    r.name, history)
+        };
+        let args: Vec<String> = (0..r.columns.len()).map(|i| format!("a{}", i)).collect();
+        s.push_str(&format!("                Request::add(Fact::{0}({1})) => {{ {3}insert_resp!(_{0}, _r{0}, {2}) }},\n", r.name, args.join(","), tup(&args), bind(false)));
+        s.push_str(&format!("                Request::del(Fact::{0}({1})) => {{ {3}remove_resp!(_{0}, _r{0}, {2}) }},\n", r.name, args.join(","), tup(&args), bind(false)));
+        let set = if r.namespaced { format!("scoped!(_r{})", r.name) } else { format!("_r{}", r.name) };
+        s.push_str(&format!("                Request::chk(Relation::{0}) => {{ {2}check!({1}) }},\n", r.name, set, bind(false)));
+        let tys: Vec<String> = r.columns.iter().map(|c| format!("{:?}", c.ty)).collect();
+        let tys = format!("&[{}]", tys.join(", "));
+        s.push_str(&format!("                Request::enm(Relation::{0}) => {{ {3}enm!({1}, {2}) }},\n", r.name, set, tys, bind(false)));
+        s.push_str(&format!("                Request::enm_at(Relation::{0}, e) => {{ {2}enm_at!(_r{0}, _h{0}, e, {1}) }},\n", r.name, tys, bind(true)));
+        s.push_str(&format!("                Request::diff(Relation::{0}, from, to) => {{ {2}diff!(_r{0}, _h{0}, from, to, {1}) }},\n", r.name, tys, bind(true)));
+    };
+    s.push_str(r#"
+            };
+        };
+    }).unwrap();
+}
+"#);
+    s
+}
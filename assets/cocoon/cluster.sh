@@ -0,0 +1,25 @@
+#!/bin/sh
+# Runs the controller as a cluster of N processes on this host, talking over loopback, with W
+# worker threads each. Process 0 reads requests on stdin and answers on stdout; the others
+# log to cluster.<p>.log. Extra arguments go to every process, e.g. `--rules ovn.dl`.
+#
+#   cargo build --release && ./cluster.sh 3 2 < requests.json
+
+set -e
+N=${1:?usage: cluster.sh PROCESSES WORKERS [ovn args]}
+W=${2:?usage: cluster.sh PROCESSES WORKERS [ovn args]}
+shift 2
+OVN=${OVN:-target/release/ovn}
+PORT=${PORT:-2101}
+
+HOSTS=$(mktemp)
+trap 'rm -f "$HOSTS"' EXIT
+for p in $(seq 0 $((N - 1))); do
+    echo "127.0.0.1:$((PORT + p))" >> "$HOSTS"
+done
+
+for p in $(seq 1 $((N - 1))); do
+    "$OVN" "$@" -w "$W" -n "$N" -p "$p" -h "$HOSTS" < /dev/null > "cluster.$p.log" 2>&1 &
+done
+"$OVN" "$@" -w "$W" -n "$N" -p 0 -h "$HOSTS"
+wait
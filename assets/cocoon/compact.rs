@@ -0,0 +1,95 @@
+//! Compaction of the arrangements behind the rules.
+//!
+//! Every single-fact update is its own epoch, and an arrangement keeps each epoch's updates
+//! apart until told that no one will ask about times before some frontier. The `_held` variants
+//! of `join_map`, `antijoin` and `distinct` build their arrangements explicitly and keep a
+//! handle on each trace in `Traces`; the request loop calls `Traces::compact` whenever it
+//! advances the inputs, so that the traces hold the live state rather than the whole history.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use timely::dataflow::*;
+
+use differential_dataflow::{Collection, Data, Hashable};
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::arrange::{ArrangeByKey, ArrangeBySelf, TraceAgent};
+use differential_dataflow::operators::group::GroupArranged;
+use differential_dataflow::trace::TraceReader;
+use differential_dataflow::trace::implementations::ord::OrdKeySpine as DefaultKeyTrace;
+
+/// A trace whose history before a frontier can be merged.
+trait Compact {
+    fn compact(&mut self, frontier: &[u64]);
+}
+
+impl<K, V, R, Tr> Compact for TraceAgent<K, V, u64, R, Tr>
+where Tr: TraceReader<K, V, u64, R>+'static, K: 'static, V: 'static, R: 'static {
+    fn compact(&mut self, frontier: &[u64]) {
+        self.advance_by(frontier);
+        self.distinguish_since(frontier);
+    }
+}
+
+/// Handles on the traces of a dataflow's arrangements.
+#[derive(Clone)]
+pub struct Traces {
+    traces: Rc<RefCell<Vec<Box<Compact>>>>
+}
+
+impl Traces {
+    pub fn new() -> Traces {
+        Traces { traces: Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    fn hold<C: Compact+'static>(&self, trace: C) {
+        self.traces.borrow_mut().push(Box::new(trace));
+    }
+
+    /// Allows every trace to merge the updates of epochs before `epoch`.
+    pub fn compact(&self, epoch: u64) {
+        for t in self.traces.borrow_mut().iter_mut() {
+            t.compact(&[epoch]);
+        }
+    }
+}
+
+/// `join_map` and `antijoin` with their arrangements held in `Traces`.
+pub trait KeyedHeld<G: Scope, K: Data, V: Data> where G::Timestamp: Lattice+Ord {
+    fn join_map_held<V2: Data, D: Data, L>(&self, traces: &Traces, other: &Collection<G, (K, V2), isize>, logic: L) -> Collection<G, D, isize>
+    where L: Fn(&K, &V, &V2)->D+'static;
+    /// Expects `keys` to be distinct: a key held twice would subtract its rows twice.
+    fn antijoin_held(&self, traces: &Traces, keys: &Collection<G, K, isize>) -> Collection<G, (K, V), isize>;
+}
+
+impl<G: Scope<Timestamp=u64>, K: Data+Hashable, V: Data> KeyedHeld<G, K, V> for Collection<G, (K, V), isize> {
+    fn join_map_held<V2: Data, D: Data, L>(&self, traces: &Traces, other: &Collection<G, (K, V2), isize>, logic: L) -> Collection<G, D, isize>
+    where L: Fn(&K, &V, &V2)->D+'static {
+        let (left, right) = (self.arrange_by_key(), other.arrange_by_key());
+        traces.hold(left.trace.clone());
+        traces.hold(right.trace.clone());
+        left.join_core(&right, move |k, v, v2| Some(logic(k, v, v2)))
+    }
+
+    fn antijoin_held(&self, traces: &Traces, keys: &Collection<G, K, isize>) -> Collection<G, (K, V), isize> {
+        let (left, keys) = (self.arrange_by_key(), keys.arrange_by_self());
+        traces.hold(left.trace.clone());
+        traces.hold(keys.trace.clone());
+        self.concat(&left.join_core(&keys, |k, v, _| Some((k.clone(), v.clone()))).negate())
+    }
+}
+
+/// `distinct` with its arrangements held in `Traces`.
+pub trait DistinctHeld<G: Scope, D: Data> where G::Timestamp: Lattice+Ord {
+    fn distinct_held(&self, traces: &Traces) -> Collection<G, D, isize>;
+}
+
+impl<G: Scope<Timestamp=u64>, D: Data+Hashable> DistinctHeld<G, D> for Collection<G, D, isize> {
+    fn distinct_held(&self, traces: &Traces) -> Collection<G, D, isize> {
+        let input = self.arrange_by_self();
+        let output = input.group_arranged(|_k, _s, t| t.push(((), 1)), DefaultKeyTrace::new());
+        traces.hold(input.trace.clone());
+        traces.hold(output.trace.clone());
+        output.as_collection(|k, _| k.clone())
+    }
+}
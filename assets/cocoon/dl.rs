@@ -0,0 +1,838 @@
+//! Parser for the rule language in `ovn.dl`.
+//!
+//! A rule file is a sequence of declarations and rules:
+//!
+//! ```text
+//! // comment
+//! input relation LogicalSwitchPort(id: u64, lswitch: u64, ptype: lport_type_t, ...) key(id)
+//! relation TrunkPort(lport: u64)
+//! realized LogicalSwitchPort
+//!
+//! TrunkPort(lport) :- LogicalSwitchPort(lport, _, _, _, _, _, _, _, _),
+//!                     LogicalSwitchPort(_, _, lport_type_t::LPortVIF{parent: lport, ..}, _, _, _, _, _, _).
+//! ```
+//!
+//! Input relations are written by clients; the others are derived by their rules. `realized R`
+//! declares the input `_realized_R`, holding what has been pushed to the data plane, and the
+//! derived `_delta_R(insert, ...)`, holding what still needs to be added or removed.
+//!
+//! `key(c, ...)` declares that no two facts of the relation agree on columns `c, ...`. Keys are
+//! documentation for clients, reported by `Request::schema`; they are not enforced.
+//!
+//! `namespace tenant: u32` gives every relation a leading column `tenant`, except those declared
+//! `global R` (with their `_realized_`/`_delta_` pairs), and keys become unique per namespace.
+//! Every atom of a namespaced relation in a rule gets the variable `tenant` as its first
+//! argument, so rules never join facts of different namespaces; the head of a rule for a
+//! namespaced relation must have a positive namespaced atom in its body, and a rule for a global
+//! relation may only read global relations.
+//!
+//! `check(expr, "message")` is a condition over the columns that every added fact must satisfy;
+//! an `add` that fails it is refused with the message.
+//!
+//! A rule body is a comma-separated list of literals, evaluated left to right:
+//!
+//! * `R(p, ...)` joins with relation `R`; arguments are patterns over variables, `_`, literals
+//!   and constructors `type::Variant{field: p, ..}`,
+//! * `not R(p, ...)` drops tuples matching `R`; all of its variables must already be bound,
+//! * `v = expr` binds a new variable,
+//! * any other expression is a filter.
+//!
+//! Expressions cover literals, constructors, function and method calls, and the usual unary and
+//! binary operators. The same AST feeds the code generator in `build.rs` and the interpreter.
+
+#![allow(dead_code)]
+
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Input,
+    Derived,
+    Realized,
+    Delta,
+}
+
+impl Kind {
+    pub fn is_input(&self) -> bool {
+        *self == Kind::Input || *self == Kind::Realized
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub name: String,
+    pub ty: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Relation {
+    pub name: String,
+    pub kind: Kind,
+    pub columns: Vec<Column>,
+    pub keys: Vec<Vec<String>>,
+    /// `check` conditions, with the message reported when one fails.
+    pub checks: Vec<(Expr, String)>,
+    /// Whether the first column is the program's namespace column.
+    pub namespaced: bool,
+    pub line: usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expr {
+    Var(String),
+    Int(String),
+    Bool(bool),
+    Str(String),
+    Bytes(String),
+    /// `path{field: e, ...}`, with `true` when the field list ends in `..` (patterns only).
+    Cons(String, Vec<(String, Expr)>, bool),
+    /// `_` (patterns only).
+    Wild,
+    Call(String, Vec<Expr>),
+    Method(Box<Expr>, String, Vec<Expr>),
+    Unop(&'static str, Box<Expr>),
+    Binop(&'static str, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Variables mentioned by the expression, in order of first occurrence.
+    pub fn vars(&self, out: &mut Vec<String>) {
+        match *self {
+            Expr::Var(ref v)                  => if !out.contains(v) { out.push(v.clone()) },
+            Expr::Cons(_, ref fs, _)          => for &(_, ref e) in fs { e.vars(out) },
+            Expr::Call(_, ref args)           => for e in args { e.vars(out) },
+            Expr::Method(ref r, _, ref args)  => { r.vars(out); for e in args { e.vars(out) } },
+            Expr::Unop(_, ref e)              => e.vars(out),
+            Expr::Binop(_, ref l, ref r)      => { l.vars(out); r.vars(out) },
+            _                                 => ()
+        }
+    }
+
+    /// `true` if the expression is also a pattern: variables, `_`, literals and constructors.
+    pub fn is_pattern(&self) -> bool {
+        match *self {
+            Expr::Call(..) | Expr::Method(..) | Expr::Unop(..) | Expr::Binop(..) => false,
+            Expr::Cons(_, ref fs, _) => fs.iter().all(|&(_, ref e)| e.is_pattern()),
+            _ => true
+        }
+    }
+
+    fn check_expr(&self) -> Result<(), String> {
+        match *self {
+            Expr::Wild                        => Err(format!("`_` is only allowed in patterns")),
+            Expr::Cons(ref p, ref fs, rest)   => {
+                if rest { return Err(format!("`..` is only allowed in patterns (in `{}`)", p)) };
+                for &(_, ref e) in fs { e.check_expr()? };
+                Ok(())
+            },
+            Expr::Call(_, ref args)           => { for e in args { e.check_expr()? }; Ok(()) },
+            Expr::Method(ref r, _, ref args)  => { r.check_expr()?; for e in args { e.check_expr()? }; Ok(()) },
+            Expr::Unop(_, ref e)              => e.check_expr(),
+            Expr::Binop(_, ref l, ref r)      => { l.check_expr()?; r.check_expr() },
+            _                                 => Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expr::Var(ref v)             => write!(f, "{}", v),
+            Expr::Int(ref i)             => write!(f, "{}", i),
+            Expr::Bool(b)                => write!(f, "{}", b),
+            Expr::Str(ref s)             => write!(f, "\"{}\"", s),
+            Expr::Bytes(ref s)           => write!(f, "b\"{}\"", s),
+            Expr::Wild                   => write!(f, "_"),
+            Expr::Cons(ref p, ref fs, rest) => {
+                write!(f, "{}{{", p)?;
+                for (i, &(ref n, ref e)) in fs.iter().enumerate() {
+                    write!(f, "{}{}: {}", if i == 0 { "" } else { ", " }, n, e)?;
+                };
+                if rest { write!(f, "{}..", if fs.is_empty() { "" } else { ", " })? };
+                write!(f, "}}")
+            },
+            Expr::Call(ref p, ref args)  => { write!(f, "{}(", p)?; comma(f, args)?; write!(f, ")") },
+            Expr::Method(ref r, ref m, ref args) => { write!(f, "{}.{}(", r, m)?; comma(f, args)?; write!(f, ")") },
+            Expr::Unop(op, ref e)        => write!(f, "{}{}", op, e),
+            Expr::Binop(op, ref l, ref r) => write!(f, "({} {} {})", l, op, r),
+        }
+    }
+}
+
+fn comma(f: &mut fmt::Formatter, args: &[Expr]) -> fmt::Result {
+    for (i, a) in args.iter().enumerate() {
+        write!(f, "{}{}", if i == 0 { "" } else { ", " }, a)?;
+    };
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct Atom {
+    pub relation: String,
+    pub args: Vec<Expr>,
+}
+
+impl Atom {
+    pub fn vars(&self, out: &mut Vec<String>) {
+        for a in &self.args { a.vars(out) }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Literal {
+    Atom(Atom),
+    Not(Atom),
+    Let(String, Expr),
+    Filter(Expr),
+}
+
+impl Literal {
+    pub fn vars(&self, out: &mut Vec<String>) {
+        match *self {
+            Literal::Atom(ref a) | Literal::Not(ref a) => a.vars(out),
+            Literal::Let(ref v, ref e) => { e.vars(out); if !out.contains(v) { out.push(v.clone()) } },
+            Literal::Filter(ref e)     => e.vars(out),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rule {
+    pub head: Atom,
+    pub body: Vec<Literal>,
+    pub line: usize,
+}
+
+impl Rule {
+    /// For each body literal, the variables used after it, by later literals or the head.
+    pub fn needed(&self) -> Vec<Vec<String>> {
+        let mut needed = vec![Vec::new(); self.body.len()];
+        let mut acc = Vec::new();
+        self.head.vars(&mut acc);
+        for i in (0..self.body.len()).rev() {
+            needed[i] = acc.clone();
+            self.body[i].vars(&mut acc);
+        };
+        needed
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub relations: Vec<Relation>,
+    pub rules: Vec<Rule>,
+    /// The column declared by `namespace`, if any.
+    pub namespace: Option<Column>,
+}
+
+impl Program {
+    /// Parses and checks a rule file. Errors start with the line (and, for
+    /// syntax errors, the column) they were found at.
+    pub fn parse(src: &str) -> Result<Program, String> {
+        let toks = lex(src)?;
+        let mut p = Parser { toks: toks, pos: 0 };
+        let mut prog = Program { relations: Vec::new(), rules: Vec::new(), namespace: None };
+        let mut realized = Vec::new();
+        let mut global = Vec::new();
+        while !p.at_eof() {
+            let line = p.line();
+            if p.eat_kw("input") {
+                p.expect_kw("relation")?;
+                let r = p.relation_decl(Kind::Input, line)?;
+                prog.declare(r)?;
+            } else if p.eat_kw("relation") {
+                let r = p.relation_decl(Kind::Derived, line)?;
+                prog.declare(r)?;
+            } else if p.eat_kw("realized") {
+                let name = p.ident()?;
+                realized.push((name, line));
+            } else if p.eat_kw("global") {
+                let name = p.ident()?;
+                global.push((name, line));
+            } else if p.eat_kw("namespace") {
+                if prog.namespace.is_some() {
+                    return Err(format!("{}: namespace declared twice", line));
+                };
+                let name = p.ident()?;
+                p.expect(":")?;
+                let ty = p.ty()?;
+                prog.namespace = Some(Column { name: name, ty: ty });
+            } else {
+                let rule = p.rule(line)?;
+                prog.rules.push(rule);
+            }
+        };
+        for &(ref name, line) in &realized {
+            prog.realize(name, line)?;
+        };
+        prog.resolve()?;
+        prog.scope(&global, &realized)?;
+        prog.check()?;
+        Ok(prog)
+    }
+
+    pub fn relation(&self, name: &str) -> Option<&Relation> {
+        self.relations.iter().find(|r| r.name == name)
+    }
+
+    pub fn rules_for<'a>(&'a self, name: &'a str) -> Vec<&'a Rule> {
+        self.rules.iter().filter(|r| r.head.relation == name).collect()
+    }
+
+    /// Relations in the order the `Fact` and `Relation` enums list them: inputs, then derived
+    /// relations, each in declaration order, then the `_realized_`/`_delta_` pairs.
+    pub fn enum_order(&self) -> Vec<&Relation> {
+        let mut v: Vec<&Relation> = self.relations.iter().filter(|r| r.kind == Kind::Input).collect();
+        v.extend(self.relations.iter().filter(|r| r.kind == Kind::Derived));
+        v.extend(self.relations.iter().filter(|r| r.kind == Kind::Realized || r.kind == Kind::Delta));
+        v
+    }
+
+    /// Relations in dataflow order: every relation after the ones its rules read, otherwise
+    /// in declaration order. Recursive rules are rejected.
+    pub fn dataflow_order(&self) -> Result<Vec<&Relation>, String> {
+        let mut done: Vec<&str> = Vec::new();
+        let mut out = Vec::new();
+        while out.len() < self.relations.len() {
+            let next = self.relations.iter().find(|r| {
+                !done.contains(&r.name.as_str()) &&
+                self.rules_for(&r.name).iter().all(|rule| rule.body.iter().all(|l| match *l {
+                    Literal::Atom(ref a) | Literal::Not(ref a) => done.contains(&a.relation.as_str()),
+                    _ => true
+                }))
+            });
+            match next {
+                Some(r) => { done.push(r.name.as_str()); out.push(r) },
+                None    => {
+                    let left: Vec<&str> = self.relations.iter().map(|r| r.name.as_str()).filter(|n| !done.contains(n)).collect();
+                    return Err(format!("recursive rules are not supported: {}", left.join(", ")));
+                }
+            }
+        };
+        Ok(out)
+    }
+
+    fn declare(&mut self, r: Relation) -> Result<(), String> {
+        if self.relation(&r.name).is_some() {
+            return Err(format!("{}: relation {} declared twice", r.line, r.name));
+        };
+        self.relations.push(r);
+        Ok(())
+    }
+
+    /// Declares `_realized_name` and `_delta_name`, and the two rules defining the latter.
+    fn realize(&mut self, name: &str, line: usize) -> Result<(), String> {
+        let (columns, keys) = match self.relation(name) {
+            Some(r) => (r.columns.clone(), r.keys.clone()),
+            None    => return Err(format!("{}: realized relation {} is not declared", line, name))
+        };
+        let realized = format!("_realized_{}", name);
+        let delta = format!("_delta_{}", name);
+        let mut dcols = vec![Column { name: "insert".to_string(), ty: "bool".to_string() }];
+        dcols.extend(columns.iter().cloned());
+        let dkeys = keys.iter().map(|k| {
+            let mut dk = vec!["insert".to_string()];
+            dk.extend(k.iter().cloned());
+            dk
+        }).collect();
+        self.declare(Relation { name: realized.clone(), kind: Kind::Realized, columns: columns.clone(), keys: keys, checks: Vec::new(), namespaced: false, line: line })?;
+        self.declare(Relation { name: delta.clone(), kind: Kind::Delta, columns: dcols, keys: dkeys, checks: Vec::new(), namespaced: false, line: line })?;
+        let vars: Vec<Expr> = columns.iter().map(|c| Expr::Var(c.name.clone())).collect();
+        for &(insert, from, not) in [(false, realized.as_str(), name), (true, name, realized.as_str())].iter() {
+            let mut head = vec![Expr::Bool(insert)];
+            head.extend(vars.iter().cloned());
+            self.rules.push(Rule {
+                head: Atom { relation: delta.clone(), args: head },
+                body: vec![Literal::Atom(Atom { relation: from.to_string(), args: vars.clone() }),
+                           Literal::Not(Atom { relation: not.to_string(), args: vars.clone() })],
+                line: line
+            });
+        };
+        Ok(())
+    }
+
+    /// The parser reads every body literal other than `not` and `v = e` as an expression; calls
+    /// to declared relations become atoms.
+    fn resolve(&mut self) -> Result<(), String> {
+        let names: Vec<String> = self.relations.iter().map(|r| r.name.clone()).collect();
+        for rule in self.rules.iter_mut() {
+            for lit in rule.body.iter_mut() {
+                let atom = match *lit {
+                    Literal::Filter(Expr::Call(ref f, ref args)) if names.contains(f) =>
+                        Some(Atom { relation: f.clone(), args: args.clone() }),
+                    _ => None
+                };
+                if let Some(a) = atom { *lit = Literal::Atom(a) };
+            }
+        };
+        Ok(())
+    }
+
+    /// Adds the namespace column to the relations not declared `global`, and the namespace
+    /// variable to their atoms.
+    fn scope(&mut self, global: &[(String, usize)], realized: &[(String, usize)]) -> Result<(), String> {
+        let ns = match self.namespace {
+            Some(ref c) => c.clone(),
+            None if global.is_empty() => return Ok(()),
+            None => return Err(format!("{}: `global` needs a `namespace` declaration", global[0].1))
+        };
+        let mut globals = Vec::new();
+        for &(ref name, line) in global {
+            if self.relation(name).is_none() {
+                return Err(format!("{}: global relation {} is not declared", line, name));
+            };
+            globals.push(name.clone());
+            if realized.iter().any(|r| r.0 == *name) {
+                globals.push(format!("_realized_{}", name));
+                globals.push(format!("_delta_{}", name));
+            };
+        };
+        for r in self.relations.iter_mut().filter(|r| !globals.contains(&r.name)) {
+            if r.columns.iter().any(|c| c.name == ns.name) {
+                return Err(format!("{}: relation {} already has a column {}", r.line, r.name, ns.name));
+            };
+            r.columns.insert(0, ns.clone());
+            for k in r.keys.iter_mut() { k.insert(0, ns.name.clone()) };
+            r.namespaced = true;
+        };
+        let var = Expr::Var(ns.name.clone());
+        for rule in self.rules.iter_mut() {
+            let (line, head) = (rule.line, rule.head.relation.clone());
+            let ctx = |msg: String| format!("{}: rule for {}: {}", line, head, msg);
+            let mut vs = Vec::new();
+            for l in &rule.body { l.vars(&mut vs) };
+            rule.head.vars(&mut vs);
+            if vs.contains(&ns.name) {
+                return Err(ctx(format!("variable {} is the namespace column", ns.name)));
+            };
+            let global_head = globals.contains(&head);
+            let mut bound = false;
+            for lit in rule.body.iter_mut() {
+                let (a, positive) = match *lit {
+                    Literal::Atom(ref mut a) => (a, true),
+                    Literal::Not(ref mut a)  => (a, false),
+                    _ => continue
+                };
+                if globals.contains(&a.relation) { continue };
+                if global_head {
+                    return Err(ctx(format!("global relation {} reads namespaced relation {}", head, a.relation)));
+                };
+                a.args.insert(0, var.clone());
+                bound = bound || positive;
+            };
+            if !global_head {
+                if !bound {
+                    return Err(ctx(format!("the body reads no namespaced relation")));
+                };
+                rule.head.args.insert(0, var.clone());
+            };
+        };
+        Ok(())
+    }
+
+    fn check(&self) -> Result<(), String> {
+        for rule in &self.rules {
+            let ctx = |msg: String| format!("{}: rule for {}: {}", rule.line, rule.head.relation, msg);
+            self.check_atom(&rule.head).map_err(&ctx)?;
+            match self.relation(&rule.head.relation) {
+                Some(r) if r.kind.is_input() => return Err(ctx(format!("{} is an input relation", r.name))),
+                _ => ()
+            };
+            for a in &rule.head.args { a.check_expr().map_err(&ctx)? };
+            let mut bound: Vec<String> = Vec::new();
+            for (i, lit) in rule.body.iter().enumerate() {
+                match *lit {
+                    Literal::Atom(ref a) => {
+                        self.check_atom(a).map_err(&ctx)?;
+                        for e in &a.args {
+                            if !e.is_pattern() { return Err(ctx(format!("argument `{}` of {} is not a pattern", e, a.relation))) }
+                        };
+                        a.vars(&mut bound);
+                    },
+                    Literal::Not(ref a) => {
+                        if i == 0 { return Err(ctx(format!("body starts with a negated atom"))) };
+                        self.check_atom(a).map_err(&ctx)?;
+                        let mut vs = Vec::new();
+                        a.vars(&mut vs);
+                        for v in vs {
+                            if !bound.contains(&v) { return Err(ctx(format!("variable {} in `not {}` is unbound", v, a.relation))) }
+                        };
+                    },
+                    Literal::Let(ref v, ref e) => {
+                        if i == 0 { return Err(ctx(format!("body starts with an assignment"))) };
+                        e.check_expr().map_err(&ctx)?;
+                        self.check_bound(e, &bound).map_err(&ctx)?;
+                        if bound.contains(v) { return Err(ctx(format!("variable {} is already bound", v))) };
+                        bound.push(v.clone());
+                    },
+                    Literal::Filter(ref e) => {
+                        if i == 0 { return Err(ctx(format!("body starts with a filter"))) };
+                        e.check_expr().map_err(&ctx)?;
+                        self.check_bound(e, &bound).map_err(&ctx)?;
+                    }
+                }
+            };
+            for a in &rule.head.args { self.check_bound(a, &bound).map_err(&ctx)? };
+        };
+        Ok(())
+    }
+
+    fn check_atom(&self, a: &Atom) -> Result<(), String> {
+        match self.relation(&a.relation) {
+            None    => Err(format!("unknown relation {}", a.relation)),
+            Some(r) => if r.columns.len() != a.args.len() {
+                Err(format!("{} has {} columns, not {}", r.name, r.columns.len(), a.args.len()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn check_bound(&self, e: &Expr, bound: &[String]) -> Result<(), String> {
+        let mut vs = Vec::new();
+        e.vars(&mut vs);
+        match vs.into_iter().find(|v| !bound.contains(v)) {
+            Some(v) => Err(format!("variable {} is unbound", v)),
+            None    => Ok(())
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Tok {
+    Ident(String),
+    Int(String),
+    Str(String),
+    Bytes(String),
+    Punct(&'static str),
+    /// `.` directly followed by a method name.
+    Dot,
+    /// `.` ending a rule.
+    End,
+}
+
+const PUNCT: &'static [&'static str] = &[
+    ":-", "::", "..", "==", "!=", "<=", ">=", "&&", "||", "<<", ">>",
+    "(", ")", "{", "}", ",", ":", "=", "<", ">", "!", "&", "|", "^", "+", "-", "*", "/", "%"];
+
+fn lex(src: &str) -> Result<Vec<(Tok, usize, usize)>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut toks = Vec::new();
+    let (mut i, mut line, mut col) = (0, 1, 1);
+    macro_rules! bump {
+        ($n:expr) => {{ for _ in 0..$n { if chars[i] == '\n' { line += 1; col = 1; } else { col += 1; }; i += 1; } }}
+    }
+    while i < chars.len() {
+        let c = chars[i];
+        let (l, cl) = (line, col);
+        if c.is_whitespace() {
+            bump!(1);
+        } else if c == '/' && chars.get(i+1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' { bump!(1) };
+        } else if c.is_alphabetic() || c == '_' {
+            if c == 'b' && chars.get(i+1) == Some(&'"') {
+                bump!(1);
+                let s = string(&chars, &mut i, &mut col, l, cl)?;
+                toks.push((Tok::Bytes(s), l, cl));
+                continue;
+            };
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { bump!(1) };
+            toks.push((Tok::Ident(chars[start..i].iter().collect()), l, cl));
+        } else if c.is_digit(10) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { bump!(1) };
+            toks.push((Tok::Int(chars[start..i].iter().collect()), l, cl));
+        } else if c == '"' {
+            let s = string(&chars, &mut i, &mut col, l, cl)?;
+            toks.push((Tok::Str(s), l, cl));
+        } else if c == '.' && chars.get(i+1) != Some(&'.') {
+            let method = chars.get(i+1).map(|n| n.is_alphabetic() || *n == '_').unwrap_or(false);
+            toks.push((if method { Tok::Dot } else { Tok::End }, l, cl));
+            bump!(1);
+        } else {
+            match PUNCT.iter().find(|p| p.chars().enumerate().all(|(k, pc)| chars.get(i+k) == Some(&pc))) {
+                Some(p) => { toks.push((Tok::Punct(p), l, cl)); bump!(p.len()); },
+                None    => return Err(format!("{}:{}: unexpected character `{}`", l, cl, c))
+            }
+        }
+    };
+    Ok(toks)
+}
+
+fn string(chars: &[char], i: &mut usize, col: &mut usize, l: usize, cl: usize) -> Result<String, String> {
+    let mut s = String::new();
+    *i += 1; *col += 1;
+    loop {
+        match chars.get(*i) {
+            None | Some(&'\n') => return Err(format!("{}:{}: unterminated string", l, cl)),
+            Some(&'"')         => { *i += 1; *col += 1; return Ok(s) },
+            Some(&'\\')        => {
+                match chars.get(*i+1) {
+                    Some(&e) => { s.push('\\'); s.push(e); *i += 2; *col += 2; },
+                    None     => return Err(format!("{}:{}: unterminated string", l, cl))
+                }
+            },
+            Some(&c)           => { s.push(c); *i += 1; *col += 1; }
+        }
+    }
+}
+
+struct Parser {
+    toks: Vec<(Tok, usize, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn at_eof(&self) -> bool { self.pos >= self.toks.len() }
+
+    fn peek(&self) -> Option<&Tok> { self.toks.get(self.pos).map(|t| &t.0) }
+
+    fn peek2(&self) -> Option<&Tok> { self.toks.get(self.pos+1).map(|t| &t.0) }
+
+    fn line(&self) -> usize { self.toks.get(self.pos).map(|t| t.1).unwrap_or(0) }
+
+    fn err<T>(&self, msg: &str) -> Result<T, String> {
+        match self.toks.get(self.pos) {
+            Some(&(ref t, l, c)) => Err(format!("{}:{}: {}, found {}", l, c, msg, show(t))),
+            None => {
+                let (l, c) = self.toks.last().map(|t| (t.1, t.2)).unwrap_or((1, 1));
+                Err(format!("{}:{}: {}, found end of file", l, c, msg))
+            }
+        }
+    }
+
+    fn eat(&mut self, p: &str) -> bool {
+        let hit = match self.peek() { Some(&Tok::Punct(q)) => q == p, _ => false };
+        if hit { self.pos += 1 };
+        hit
+    }
+
+    fn expect(&mut self, p: &str) -> Result<(), String> {
+        if self.eat(p) { Ok(()) } else { self.err(&format!("expected `{}`", p)) }
+    }
+
+    fn eat_kw(&mut self, kw: &str) -> bool {
+        let hit = match self.peek() { Some(&Tok::Ident(ref s)) => s == kw, _ => false };
+        if hit { self.pos += 1 };
+        hit
+    }
+
+    fn expect_kw(&mut self, kw: &str) -> Result<(), String> {
+        if self.eat_kw(kw) { Ok(()) } else { self.err(&format!("expected `{}`", kw)) }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.peek().cloned() {
+            Some(Tok::Ident(s)) => { self.pos += 1; Ok(s) },
+            _ => self.err("expected identifier")
+        }
+    }
+
+    fn relation_decl(&mut self, kind: Kind, line: usize) -> Result<Relation, String> {
+        let name = self.ident()?;
+        self.expect("(")?;
+        let mut columns = Vec::new();
+        while !self.eat(")") {
+            if !columns.is_empty() { self.expect(",")? };
+            let cname = self.ident()?;
+            self.expect(":")?;
+            let ty = self.ty()?;
+            columns.push(Column { name: cname, ty: ty });
+        };
+        if columns.is_empty() {
+            return Err(format!("{}: relation {} has no columns", line, name));
+        };
+        let mut keys = Vec::new();
+        while self.eat_kw("key") {
+            self.expect("(")?;
+            let mut key = Vec::new();
+            while !self.eat(")") {
+                if !key.is_empty() { self.expect(",")? };
+                let c = self.ident()?;
+                if !columns.iter().any(|col| col.name == c) {
+                    return Err(format!("{}: relation {} has no column {}", line, name, c));
+                };
+                key.push(c);
+            };
+            if key.is_empty() {
+                return Err(format!("{}: relation {} has an empty key", line, name));
+            };
+            keys.push(key);
+        };
+        let mut checks = Vec::new();
+        while self.eat_kw("check") {
+            self.expect("(")?;
+            let cond = self.expr()?;
+            cond.check_expr().map_err(|e| format!("{}: relation {}: {}", line, name, e))?;
+            let mut vars = Vec::new();
+            cond.vars(&mut vars);
+            if let Some(v) = vars.iter().find(|v| !columns.iter().any(|col| &col.name == *v)) {
+                return Err(format!("{}: relation {} has no column {}", line, name, v));
+            };
+            self.expect(",")?;
+            let msg = match self.peek().cloned() {
+                Some(Tok::Str(s)) => { self.pos += 1; s },
+                _ => return self.err("expected the message of the check")
+            };
+            self.expect(")")?;
+            checks.push((cond, msg));
+        };
+        Ok(Relation { name: name, kind: kind, columns: columns, keys: keys, checks: checks, namespaced: false, line: line })
+    }
+
+    /// A type is copied through as text: a path, optionally with `<...>` arguments.
+    fn ty(&mut self) -> Result<String, String> {
+        let mut s = self.ident()?;
+        while self.eat("::") { s.push_str("::"); s.push_str(&self.ident()?) };
+        if self.eat("<") {
+            s.push('<');
+            loop {
+                s.push_str(&self.ty()?);
+                if self.eat(">") { break };
+                self.expect(",")?;
+                s.push_str(", ");
+            };
+            s.push('>');
+        };
+        Ok(s)
+    }
+
+    fn rule(&mut self, line: usize) -> Result<Rule, String> {
+        let relation = self.ident()?;
+        self.expect("(")?;
+        let args = self.args(")")?;
+        self.expect(":-")?;
+        let mut body = Vec::new();
+        loop {
+            body.push(self.literal()?);
+            if self.eat(",") { continue };
+            match self.peek() {
+                Some(&Tok::End) => { self.pos += 1; break },
+                _ => return self.err("expected `,` or `.`")
+            }
+        };
+        Ok(Rule { head: Atom { relation: relation, args: args }, body: body, line: line })
+    }
+
+    fn literal(&mut self) -> Result<Literal, String> {
+        if self.eat_kw("not") {
+            let relation = self.ident()?;
+            self.expect("(")?;
+            let args = self.args(")")?;
+            return Ok(Literal::Not(Atom { relation: relation, args: args }));
+        };
+        let assign = match (self.peek(), self.peek2()) {
+            (Some(&Tok::Ident(_)), Some(&Tok::Punct("="))) => true,
+            _ => false
+        };
+        if assign {
+            let v = self.ident()?;
+            self.expect("=")?;
+            return Ok(Literal::Let(v, self.expr()?));
+        };
+        Ok(Literal::Filter(self.expr()?))
+    }
+
+    fn args(&mut self, close: &str) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        while !self.eat(close) {
+            if !args.is_empty() { self.expect(",")? };
+            args.push(self.expr()?);
+        };
+        Ok(args)
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> { self.binary(0) }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        const LEVELS: &'static [&'static [&'static str]] = &[
+            &["||"], &["&&"], &["==", "!=", "<", "<=", ">", ">="], &["|"], &["^"], &["&"],
+            &["<<", ">>"], &["+", "-"], &["*", "/", "%"]];
+        if level == LEVELS.len() { return self.unary() };
+        let mut e = self.binary(level + 1)?;
+        loop {
+            let op = match self.peek() {
+                Some(&Tok::Punct(p)) if LEVELS[level].contains(&p) => p,
+                _ => return Ok(e)
+            };
+            self.pos += 1;
+            let r = self.binary(level + 1)?;
+            e = Expr::Binop(op, Box::new(e), Box::new(r));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        for op in ["!", "-", "&"].iter() {
+            if self.eat(op) { return Ok(Expr::Unop(*op, Box::new(self.unary()?))) };
+        };
+        let mut e = self.primary()?;
+        while self.peek() == Some(&Tok::Dot) {
+            self.pos += 1;
+            let m = self.ident()?;
+            self.expect("(")?;
+            let args = self.args(")")?;
+            e = Expr::Method(Box::new(e), m, args);
+        };
+        Ok(e)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.peek().cloned() {
+            Some(Tok::Int(i))   => { self.pos += 1; Ok(Expr::Int(i)) },
+            Some(Tok::Str(s))   => { self.pos += 1; Ok(Expr::Str(s)) },
+            Some(Tok::Bytes(s)) => { self.pos += 1; Ok(Expr::Bytes(s)) },
+            Some(Tok::Punct("(")) => {
+                self.pos += 1;
+                let e = self.expr()?;
+                self.expect(")")?;
+                Ok(e)
+            },
+            Some(Tok::Ident(_)) => {
+                let mut path = self.ident()?;
+                match path.as_str() {
+                    "_"     => return Ok(Expr::Wild),
+                    "true"  => return Ok(Expr::Bool(true)),
+                    "false" => return Ok(Expr::Bool(false)),
+                    _       => ()
+                };
+                let mut qualified = false;
+                while self.eat("::") {
+                    path.push_str("::");
+                    path.push_str(&self.ident()?);
+                    qualified = true;
+                };
+                if self.eat("(") {
+                    let args = self.args(")")?;
+                    Ok(Expr::Call(path, args))
+                } else if qualified && self.eat("{") {
+                    let mut fields = Vec::new();
+                    let mut rest = false;
+                    while !self.eat("}") {
+                        if !fields.is_empty() || rest { self.expect(",")? };
+                        if self.eat("..") { rest = true; self.expect("}")?; break };
+                        let f = self.ident()?;
+                        self.expect(":")?;
+                        fields.push((f, self.expr()?));
+                    };
+                    Ok(Expr::Cons(path, fields, rest))
+                } else if qualified {
+                    self.err(&format!("expected `{{` or `(` after `{}`", path))
+                } else {
+                    Ok(Expr::Var(path))
+                }
+            },
+            _ => self.err("expected expression")
+        }
+    }
+}
+
+fn show(t: &Tok) -> String {
+    match *t {
+        Tok::Ident(ref s) | Tok::Int(ref s) => format!("`{}`", s),
+        Tok::Str(ref s)   => format!("\"{}\"", s),
+        Tok::Bytes(ref s) => format!("b\"{}\"", s),
+        Tok::Punct(p)     => format!("`{}`", p),
+        Tok::Dot | Tok::End => format!("`.`"),
+    }
+}
@@ -0,0 +1,66 @@
+//! Writes the requests of a synthetic topology to stdout, one per line, for `ovn` to read:
+//! the topology of `Sizes::from_args` one transaction at a time, then `--churn N` transactions
+//! changing it (default 0).
+//!
+//!   cargo build --release && target/release/gen --switches 100 --ports 20 --churn 1000 | target/release/ovn
+
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use std::io::{stdout, BufWriter, Write};
+
+mod topology;
+
+use topology::{Sizes, Topology};
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let churn = match args.iter().position(|a| a == "--churn") {
+        Some(i) if i + 1 < args.len() => {
+            let n = args.remove(i + 1);
+            args.remove(i);
+            n.parse::<u64>().unwrap_or_else(|_| { eprintln!("--churn: {} is not a number", n); std::process::exit(-1) })
+        },
+        Some(_) => { eprintln!("--churn: missing number"); std::process::exit(-1) },
+        None    => 0
+    };
+    let sizes = match Sizes::from_args(&mut args) {
+        Ok(s) if args.is_empty() => s,
+        Ok(_)  => { eprintln!("unknown arguments: {}", args.join(" ")); std::process::exit(-1) },
+        Err(e) => { eprintln!("{}", e); std::process::exit(-1) }
+    };
+
+    let out = stdout();
+    let mut out = BufWriter::new(out.lock());
+    let mut write = |txn: &topology::Txn| {
+        writeln!(out, "\"start\"").unwrap();
+        for r in txn {
+            writeln!(out, "{}", r).unwrap();
+        };
+        writeln!(out, "\"commit\"").unwrap();
+    };
+
+    let mut topo = Topology::new(sizes);
+    let (mut txns, mut changes) = (0, 0);
+    for txn in topo.build() {
+        write(&txn);
+        txns += 1;
+        changes += txn.len();
+    };
+    for _ in 0..churn {
+        match topo.churn() {
+            Some((_, txn)) => {
+                write(&txn);
+                txns += 1;
+                changes += txn.len();
+            },
+            None => {
+                eprintln!("the topology has nothing to churn");
+                break;
+            }
+        };
+    };
+    eprintln!("{} transactions, {} changes", txns, changes);
+}
@@ -0,0 +1,1023 @@
+//! Interpreter for rule files, run by `ovn --rules FILE` instead of the compiled rules.
+//!
+//! The file is parsed at startup by `dl.rs`, and each rule becomes the chain of `join_map`s,
+//! `antijoin`s, `filter`s and `map`s that `build.rs` would generate for it, over rows of
+//! `Value`s instead of typed tuples. Requests and responses have the same JSON form as with
+//! the compiled rules, except that the interpreter does not allocate MAC and IP addresses: that
+//! is Rust code written against the compiled relations.
+//!
+//! Column types must be types of the prelude, whose `serde` forms decide what their values look
+//! like. Functions and methods called by rules are limited to the builtins in `call`.
+
+use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{stdout, Read, Write};
+use std::rc::Rc;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json as json;
+
+use timely;
+use timely::dataflow::*;
+use timely::dataflow::operators::*;
+
+use differential_dataflow::Collection;
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::*;
+use differential_dataflow::lattice::Lattice;
+
+use dl::{Atom, Expr, Literal, Program, Rule};
+
+use super::*;
+
+/// A column value, shaped like its JSON form: enum variants are constructors with their fields
+/// sorted by name, except variants without fields, which are strings, and `U128`s are strings
+/// of decimal digits.
+#[derive(Eq, PartialOrd, PartialEq, Ord, Debug, Clone, Hash)]
+enum Value {
+    Bool(bool),
+    Int(u64),
+    Str(String),
+    Seq(Vec<Value>),
+    Cons(String, Vec<(String, Value)>),
+}
+unsafe_abomonate!(Value);
+
+type Row = Vec<Value>;
+
+impl Value {
+    fn from_json(v: &json::Value) -> Result<Value, String> {
+        match *v {
+            json::Value::Bool(b)       => Ok(Value::Bool(b)),
+            json::Value::Number(ref n) => n.as_u64().map(Value::Int).ok_or_else(|| format!("unsupported number {}", n)),
+            json::Value::String(ref s) => Ok(Value::Str(s.clone())),
+            json::Value::Array(ref vs) => vs.iter().map(Value::from_json).collect::<Result<_, _>>().map(Value::Seq),
+            _                          => match json_variant(v)? {
+                (k, &json::Value::Object(ref m)) => {
+                    let mut fs = Vec::new();
+                    for (f, x) in m.iter() {
+                        fs.push((f.clone(), Value::from_json(x)?));
+                    };
+                    fs.sort();
+                    Ok(Value::Cons(k.to_string(), fs))
+                },
+                _ => Err(format!("unsupported value {}", v))
+            }
+        }
+    }
+
+    fn to_json(&self) -> json::Value {
+        match *self {
+            Value::Bool(b)             => json::Value::Bool(b),
+            Value::Int(i)              => json::Value::from(i),
+            Value::Str(ref s)          => json::Value::String(s.clone()),
+            Value::Seq(ref vs)         => json::Value::Array(vs.iter().map(|v| v.to_json()).collect()),
+            Value::Cons(ref k, ref fs) => {
+                let body = fs.iter().map(|&(ref f, ref v)| (f.clone(), v.to_json())).collect();
+                let mut m = json::Map::new();
+                m.insert(k.clone(), json::Value::Object(body));
+                json::Value::Object(m)
+            }
+        }
+    }
+
+    fn to_bool(&self) -> Result<bool, String> {
+        match *self {
+            Value::Bool(b) => Ok(b),
+            _              => Err(format!("expected a boolean, found {}", self.to_json()))
+        }
+    }
+
+    fn to_int(&self) -> Result<u64, String> {
+        match *self {
+            Value::Int(i) => Ok(i),
+            _             => Err(format!("expected an integer, found {}", self.to_json()))
+        }
+    }
+}
+
+// as JSON, for the shards that workers send to the front end
+impl Serialize for Value {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de> ::serde::Deserialize<'de> for Value {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        let v = <json::Value as ::serde::Deserialize>::deserialize(deserializer)?;
+        Value::from_json(&v).map_err(::serde::de::Error::custom)
+    }
+}
+
+/// Expands to `$f::<T>($arg)` for the prelude type `T` named by `$ty`.
+macro_rules! typed {
+    ($ty:expr, $f:ident, $arg:expr) => {
+        match &$ty.chars().filter(|c| !c.is_whitespace()).collect::<String>()[..] {
+            "bool"                   => $f::<bool>($arg),
+            "u8"                     => $f::<u8>($arg),
+            "u16"                    => $f::<u16>($arg),
+            "u32"                    => $f::<u32>($arg),
+            "u64"                    => $f::<u64>($arg),
+            "String"                 => $f::<String>($arg),
+            "Vec<u8>"                => $f::<Vec<u8>>($arg),
+            "ip4_addr_t"             => $f::<Ip4Addr>($arg),
+            "mac_addr_t"             => $f::<MacAddr>($arg),
+            "U128"                   => $f::<U128>($arg),
+            "Uint"                   => $f::<Uint>($arg),
+            "__lambda"               => $f::<__lambda>($arg),
+            "lswitch_type_t"         => $f::<lswitch_type_t>($arg),
+            "ip4_subnet_t"           => $f::<ip4_subnet_t>($arg),
+            "ip6_subnet_t"           => $f::<ip6_subnet_t>($arg),
+            "ip_subnet_t"            => $f::<ip_subnet_t>($arg),
+            "opt_subnet_t"           => $f::<opt_subnet_t>($arg),
+            "lrouter_type_t"         => $f::<lrouter_type_t>($arg),
+            "lrouter_port_type_t"    => $f::<lrouter_port_type_t>($arg),
+            "opt_peer_t"             => $f::<opt_peer_t>($arg),
+            "dhcp4_options_t"        => $f::<dhcp4_options_t>($arg),
+            "duid_t"                 => $f::<duid_t>($arg),
+            "dhcp6_options_t"        => $f::<dhcp6_options_t>($arg),
+            "lport_type_t"           => $f::<lport_type_t>($arg),
+            "opt_dhcp4_options_id_t" => $f::<opt_dhcp4_options_id_t>($arg),
+            "opt_dhcp6_options_id_t" => $f::<opt_dhcp6_options_id_t>($arg),
+            "ip_addr_t"              => $f::<ip_addr_t>($arg),
+            "opt_ip_addr_t"          => $f::<opt_ip_addr_t>($arg),
+            "ip4_addr_port_t"        => $f::<ip4_addr_port_t>($arg),
+            "ip6_addr_port_t"        => $f::<ip6_addr_port_t>($arg),
+            "acl_dir_t"              => $f::<acl_dir_t>($arg),
+            "acl_action_t"           => $f::<acl_action_t>($arg),
+            "nat_type_t"             => $f::<nat_type_t>($arg),
+            "opt_mac_addr_t"         => $f::<opt_mac_addr_t>($arg),
+            "opt_lport_id_t"         => $f::<opt_lport_id_t>($arg),
+            "port_sec_type_t"        => $f::<port_sec_type_t>($arg),
+            "destination_t"          => $f::<destination_t>($arg),
+            "encap_type_t"           => $f::<encap_type_t>($arg),
+            t                        => Err(format!("unsupported column type {}", t))
+        }
+    }
+}
+
+fn known<T>(_: ()) -> Result<(), String> {
+    Ok(())
+}
+
+fn decode_as<T: Serialize + DeserializeOwned>(v: json::Value) -> Result<Value, String> {
+    untyped(&typed::<T>(&v)?)
+}
+
+fn encode_as<T: Serialize + DeserializeOwned>(v: &Value) -> Result<json::Value, String> {
+    json::to_value(&typed::<T>(&v.to_json())?).map_err(|e| e.to_string())
+}
+
+/// Reads a value of type `ty` from any JSON form the compiled controller accepts.
+fn decode(ty: &str, v: json::Value) -> Result<Value, String> {
+    typed!(ty, decode_as, v)
+}
+
+/// The JSON form the compiled controller would print for a value of type `ty`.
+fn encode(ty: &str, v: &Value) -> Result<json::Value, String> {
+    typed!(ty, encode_as, v)
+}
+
+fn typed<T: DeserializeOwned>(v: &json::Value) -> Result<T, String> {
+    json::from_value(v.clone()).map_err(|e| e.to_string())
+}
+
+fn untyped<T: Serialize>(x: &T) -> Result<Value, String> {
+    Value::from_json(&structured_json(x)?)
+}
+
+/// Parses an integer literal, with an optional radix prefix and type suffix.
+fn int(lit: &str) -> Result<u64, String> {
+    let s: String = lit.chars().filter(|&c| c != '_').collect();
+    let (digits, radix) = if s.starts_with("0x") { (&s[2..], 16) }
+                          else if s.starts_with("0o") { (&s[2..], 8) }
+                          else if s.starts_with("0b") { (&s[2..], 2) }
+                          else { (&s[..], 10) };
+    let digits = match digits.find(|c: char| c == 'u' || c == 'i') {
+        Some(i) => &digits[..i],
+        None    => digits
+    };
+    u64::from_str_radix(digits, radix).map_err(|_| format!("invalid integer literal {}", lit))
+}
+
+/// The variant named by a constructor path `type::Variant`.
+fn variant(path: &str) -> &str {
+    path.rsplit("::").next().unwrap()
+}
+
+fn cons(path: &str, fields: Vec<(String, Value)>) -> Value {
+    let mut fields = fields;
+    fields.sort();
+    if fields.is_empty() { Value::Str(variant(path).to_string()) } else { Value::Cons(variant(path).to_string(), fields) }
+}
+
+/// Builtin functions, and methods called `.name` with their receiver as first argument.
+fn call(name: &str, args: &[Value]) -> Result<Value, String> {
+    match (name, args) {
+        ("U128::parse_bytes", &[Value::Str(ref s), Value::Int(radix)]) => {
+            match u128::from_str_radix(s, radix as u32) {
+                Ok(x)  => Ok(Value::Str(x.to_string())),
+                Err(_) => Err(format!("invalid 128-bit integer value: {}", s))
+            }
+        },
+        ("U128::prefix_mask", &[Value::Int(len)]) => untyped(&U128::prefix_mask(len.min(128) as u32)),
+        ("dhcp4_options_encode", &[ref opts]) => {
+            let bytes = dhcp4_options_encode(&typed(&opts.to_json())?);
+            untyped(&bytes)
+        },
+        ("dhcp6_options_encode", &[ref addr, ref opts]) => {
+            let bytes = dhcp6_options_encode(&typed(&addr.to_json())?, &typed(&opts.to_json())?);
+            untyped(&bytes)
+        },
+        (".is_nested_in", &[ref ptype, Value::Int(lport)]) => {
+            let ptype: lport_type_t = typed(&ptype.to_json())?;
+            Ok(Value::Bool(ptype.is_nested_in(lport)))
+        },
+        (".contains", &[ref subnet, Value::Int(ip)]) => {
+            let subnet: ip4_subnet_t = typed(&subnet.to_json())?;
+            Ok(Value::Bool(subnet.contains(ip as u32)))
+        },
+        (".contains", &[ref subnet, ref ip]) => {
+            let subnet: ip6_subnet_t = typed(&subnet.to_json())?;
+            Ok(Value::Bool(subnet.contains(typed(&ip.to_json())?)))
+        },
+        (".network", &[ref subnet]) => {
+            let subnet: ip6_subnet_t = typed(&subnet.to_json())?;
+            untyped(&subnet.network())
+        },
+        _ => Err(format!("unknown function {} for arguments {:?}", name, args))
+    }
+}
+
+/// Evaluates an expression over a row binding the variables `vars`.
+fn eval(e: &Expr, vars: &[String], row: &[Value]) -> Result<Value, String> {
+    let evals = |es: &[Expr]| es.iter().map(|e| eval(e, vars, row)).collect::<Result<Vec<_>, _>>();
+    match *e {
+        Expr::Var(ref v)                     => Ok(row[vars.iter().position(|x| x == v).unwrap()].clone()),
+        Expr::Int(ref i)                     => int(i).map(Value::Int),
+        Expr::Bool(b)                        => Ok(Value::Bool(b)),
+        Expr::Str(ref s) | Expr::Bytes(ref s) => Ok(Value::Str(s.clone())),
+        Expr::Wild                           => Err(format!("`_` is not a value")),
+        Expr::Cons(ref p, ref fs, _)         => {
+            let mut fields = Vec::new();
+            for &(ref n, ref e) in fs {
+                fields.push((n.clone(), eval(e, vars, row)?));
+            };
+            Ok(cons(p, fields))
+        },
+        Expr::Call(ref f, ref args)          => call(f, &evals(args)?),
+        Expr::Method(ref r, ref m, ref args) => {
+            let mut all = vec![eval(r, vars, row)?];
+            all.extend(evals(args)?);
+            call(&format!(".{}", m), &all)
+        },
+        Expr::Unop(op, ref e)                => {
+            let v = eval(e, vars, row)?;
+            match (op, v) {
+                ("&", v)              => Ok(v),
+                ("!", Value::Bool(b)) => Ok(Value::Bool(!b)),
+                ("!", Value::Int(i))  => Ok(Value::Int(!i)),
+                ("-", Value::Int(i))  => Ok(Value::Int(i.wrapping_neg())),
+                (op, v)               => Err(format!("cannot apply {} to {}", op, v.to_json()))
+            }
+        },
+        Expr::Binop("&&", ref l, ref r)      => {
+            if eval(l, vars, row)?.to_bool()? { eval(r, vars, row) } else { Ok(Value::Bool(false)) }
+        },
+        Expr::Binop("||", ref l, ref r)      => {
+            if eval(l, vars, row)?.to_bool()? { Ok(Value::Bool(true)) } else { eval(r, vars, row) }
+        },
+        Expr::Binop(op, ref l, ref r)        => {
+            let (l, r) = (eval(l, vars, row)?, eval(r, vars, row)?);
+            match op {
+                "==" => return Ok(Value::Bool(l == r)),
+                "!=" => return Ok(Value::Bool(l != r)),
+                "<"  => return Ok(Value::Bool(l < r)),
+                "<=" => return Ok(Value::Bool(l <= r)),
+                ">"  => return Ok(Value::Bool(l > r)),
+                ">=" => return Ok(Value::Bool(l >= r)),
+                _    => ()
+            };
+            let (a, b) = (l.to_int()?, r.to_int()?);
+            let res = match op {
+                "+"  => a.checked_add(b),
+                "-"  => a.checked_sub(b),
+                "*"  => a.checked_mul(b),
+                "/"  => a.checked_div(b),
+                "%"  => a.checked_rem(b),
+                "&"  => Some(a & b),
+                "|"  => Some(a | b),
+                "^"  => Some(a ^ b),
+                "<<" => if b < 64 { Some(a << b) } else { None },
+                ">>" => if b < 64 { Some(a >> b) } else { None },
+                _    => unreachable!()
+            };
+            res.map(Value::Int).ok_or_else(|| format!("overflow in {} {} {}", a, op, b))
+        }
+    }
+}
+
+/// Matches a value against a pattern, binding its new variables in `vars` and `row`.
+fn bind(p: &Expr, v: &Value, vars: &mut Vec<String>, row: &mut Row) -> Result<bool, String> {
+    match *p {
+        Expr::Wild => Ok(true),
+        Expr::Var(ref x) => match vars.iter().position(|y| y == x) {
+            Some(i) => Ok(row[i] == *v),
+            None    => { vars.push(x.clone()); row.push(v.clone()); Ok(true) }
+        },
+        Expr::Cons(ref path, ref fs, rest) => match *v {
+            Value::Str(ref s)          => Ok(fs.is_empty() && s == variant(path)),
+            Value::Cons(ref k, ref vfs) => {
+                if k != variant(path) || (!rest && fs.len() != vfs.len()) {
+                    return Ok(false)
+                };
+                for &(ref f, ref fp) in fs {
+                    match vfs.iter().find(|&&(ref vf, _)| vf == f) {
+                        Some(&(_, ref fv)) => if !bind(fp, fv, vars, row)? { return Ok(false) },
+                        None               => return Ok(false)
+                    }
+                };
+                Ok(true)
+            },
+            _ => Ok(false)
+        },
+        _ => Ok(eval(p, &[], &[])? == *v)
+    }
+}
+
+/// Matches a row of relation `atom.relation` against the atom, and projects the variables
+/// bound to `wanted`.
+fn scan_row(atom: &Atom, wanted: &[String], row: &Row) -> Result<Option<Row>, String> {
+    let mut vars = Vec::new();
+    let mut vals = Vec::new();
+    for (p, v) in atom.args.iter().zip(row.iter()) {
+        if !bind(p, v, &mut vars, &mut vals)? {
+            return Ok(None)
+        };
+    };
+    Ok(Some(project(&vars, &vals, wanted)))
+}
+
+fn project(vars: &[String], row: &[Value], wanted: &[String]) -> Row {
+    wanted.iter().map(|w| row[vars.iter().position(|v| v == w).unwrap()].clone()).collect()
+}
+
+fn keep(vs: &[String], needed: &[String]) -> Vec<String> {
+    vs.iter().filter(|v| needed.contains(v)).cloned().collect()
+}
+
+/// Drops the rows for which evaluating a rule failed, after reporting why.
+fn report<T>(line: usize, r: Result<T, String>) -> Option<T> {
+    match r {
+        Ok(x)  => Some(x),
+        Err(e) => { eprintln!("rule at line {}: {}", line, e); None }
+    }
+}
+
+/// `rel`, filtered to rows matching `atom` and mapped to the variables in `wanted`.
+fn scan<G: Scope>(rel: &Collection<G, Row, isize>, atom: &Atom, wanted: Vec<String>, line: usize) -> Collection<G, Row, isize>
+where G::Timestamp: Lattice+Ord {
+    let atom = atom.clone();
+    rel.flat_map(move |row| report(line, scan_row(&atom, &wanted, &row)).and_then(|r| r))
+}
+
+/// The collection of one rule's head rows, following `rule_chain` in `build.rs`, with its
+/// arrangements held in `traces`.
+fn rule_collection<G: Scope<Timestamp=u64>>(rule: &Rule, rels: &HashMap<String, Collection<G, Row, isize>>, traces: &compact::Traces) -> Collection<G, Row, isize> {
+    let line = rule.line;
+    let needed = rule.needed();
+    let (mut state, mut coll) = match rule.body[0] {
+        Literal::Atom(ref a) => {
+            let mut vs = Vec::new();
+            a.vars(&mut vs);
+            let next = keep(&vs, &needed[0]);
+            (next.clone(), scan(&rels[&a.relation], a, next, line))
+        },
+        _ => unreachable!()
+    };
+    for (i, lit) in rule.body.iter().enumerate().skip(1) {
+        let next = match *lit {
+            Literal::Atom(ref a) => {
+                let mut vs = Vec::new();
+                a.vars(&mut vs);
+                let key: Vec<String> = vs.iter().filter(|v| state.contains(v)).cloned().collect();
+                let new: Vec<String> = vs.iter().filter(|v| !state.contains(v) && needed[i].contains(v)).cloned().collect();
+                let rest: Vec<String> = state.iter().filter(|v| !key.contains(v) && needed[i].contains(v)).cloned().collect();
+                let mut next = keep(&state, &needed[i]);
+                next.extend(new.iter().cloned());
+                let mut wanted = key.clone();
+                wanted.extend(new.iter().cloned());
+                let nkey = key.len();
+                let right = scan(&rels[&a.relation], a, wanted, line)
+                    .map(move |mut r| { let n = r.split_off(nkey); (r, n) });
+                let mut vars = key.clone();
+                vars.extend(rest.iter().cloned());
+                vars.extend(new.iter().cloned());
+                let (s, n) = (state.clone(), next.clone());
+                coll = coll.map(move |row| (project(&s, &row, &key), project(&s, &row, &rest)))
+                           .join_map_held(traces, &right, move |kv, rv, nv| {
+                               let mut row = kv.clone();
+                               row.extend(rv.iter().cloned());
+                               row.extend(nv.iter().cloned());
+                               project(&vars, &row, &n)
+                           });
+                next
+            },
+            Literal::Not(ref a) => {
+                let mut key = Vec::new();
+                a.vars(&mut key);
+                let rest: Vec<String> = state.iter().filter(|v| !key.contains(v) && needed[i].contains(v)).cloned().collect();
+                let next = keep(&state, &needed[i]);
+                let mut vars = key.clone();
+                vars.extend(rest.iter().cloned());
+                let keys = scan(&rels[&a.relation], a, key.clone(), line);
+                let (s, n) = (state.clone(), next.clone());
+                coll = coll.map(move |row| (project(&s, &row, &key), project(&s, &row, &rest)))
+                           .antijoin_held(traces, &keys.distinct_held(traces))
+                           .map(move |(kv, rv)| {
+                               let mut row = kv;
+                               row.extend(rv);
+                               project(&vars, &row, &n)
+                           });
+                next
+            },
+            Literal::Let(ref v, ref e) => {
+                let mut next = keep(&state, &needed[i]);
+                if needed[i].contains(v) { next.push(v.clone()) };
+                let (s, e, n) = (state.clone(), e.clone(), next.clone());
+                let mut vars = state.clone();
+                vars.push(v.clone());
+                coll = coll.flat_map(move |row| {
+                    report(line, eval(&e, &s, &row)).map(|x| {
+                        let mut row = row;
+                        row.push(x);
+                        project(&vars, &row, &n)
+                    })
+                });
+                next
+            },
+            Literal::Filter(ref e) => {
+                let (s, e) = (state.clone(), e.clone());
+                coll = coll.filter(move |row| report(line, eval(&e, &s, row).and_then(|v| v.to_bool())).unwrap_or(false));
+                let next = keep(&state, &needed[i]);
+                if next != state {
+                    let (s, n) = (state.clone(), next.clone());
+                    coll = coll.map(move |row| project(&s, &row, &n));
+                };
+                next
+            }
+        };
+        state = next;
+    };
+    let head = rule.head.args.clone();
+    coll.flat_map(move |row| report(line, head.iter().map(|e| eval(e, &state, &row)).collect::<Result<Row, _>>()))
+}
+
+/// Parses a rule file and checks that the prelude has all its column types.
+fn load(path: &str) -> Result<Program, String> {
+    let mut src = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut src)).map_err(|e| e.to_string())?;
+    let prog = Program::parse(&src)?;
+    for r in &prog.relations {
+        for c in &r.columns {
+            typed!(c.ty, known, ()).map_err(|e| format!("{}: relation {}: {}", r.line, r.name, e))?;
+        };
+    };
+    prog.dataflow_order()?;
+    Ok(prog)
+}
+
+/// Reads the relation and row of an `add` or `del` request, `{"Relation": [columns]}`, or
+/// `{"Relation": column}` for relations with one column.
+fn fact(prog: &Program, v: &json::Value) -> Result<(String, Row), String> {
+    let (name, args) = json_variant(v)?;
+    let rel = prog.relation(name).ok_or_else(|| format!("unknown relation {}", name))?;
+    let args = if rel.columns.len() == 1 {
+        vec![args.clone()]
+    } else {
+        match args.as_array() {
+            Some(a) if a.len() == rel.columns.len() => a.clone(),
+            _ => return Err(format!("{} expects {} columns, found {}", name, rel.columns.len(), args))
+        }
+    };
+    let mut row = Vec::new();
+    for (c, a) in rel.columns.iter().zip(args.into_iter()) {
+        row.push(decode(&c.ty, a).map_err(|e| format!("{}.{}: {}", name, c.name, e))?);
+    };
+    Ok((name.to_string(), row))
+}
+
+/// Reads the fact of an `add` or `del` request, refusing writes to derived relations and to
+/// those of the address allocator unless they are allowed.
+fn write(prog: &Program, req: &json::Value, f: &json::Value) -> Result<(String, Row), String> {
+    let (rel, row) = fact(prog, f)?;
+    if !prog.relation(&rel).unwrap().kind.is_input() || IPAM_ALLOCATED.contains(&rel.as_str()) {
+        derived_write(&rel, &req.to_string())?;
+    };
+    Ok((rel, row))
+}
+
+/// Splits a `scoped` request into its namespace and request, checking the request as
+/// `in_namespace` does for the compiled rules.
+fn unscope(prog: &Program, req: json::Value) -> Result<(Option<Value>, json::Value), String> {
+    let (ns, inner) = match json_variant(&req) {
+        Ok(("scoped", &json::Value::Array(ref a))) if a.len() == 2 => (a[0].clone(), a[1].clone()),
+        Ok(("scoped", a)) => return Err(format!("scoped expects a namespace and a request, found {}", a)),
+        _                 => return Ok((None, req))
+    };
+    let col = prog.namespace.as_ref().ok_or_else(|| format!("the rules declare no namespace"))?;
+    let ns = decode(&col.ty, ns).map_err(|e| format!("namespace: {}", e))?;
+    let cmd = match inner {
+        json::Value::String(ref s) => s.clone(),
+        _                          => json_variant(&inner)?.0.to_string()
+    };
+    match cmd.as_str() {
+        "add" | "del" => {
+            let (rel, row) = fact(prog, json_variant(&inner)?.1)?;
+            if !prog.relation(&rel).unwrap().namespaced {
+                return Err(format!("{} is global; it cannot be written in a namespace", inner));
+            } else if row[0] != ns {
+                return Err(format!("{} is in namespace {}, not {}", inner, row[0].to_json(), ns.to_json()));
+            };
+        },
+        "start" | "start_with" | "rollback" | "commit" | "chk" | "enm" | "schema" => (),
+        _ => return Err(format!("{} is not available in a namespace", inner))
+    };
+    Ok((Some(ns), inner))
+}
+
+fn valid_as<T: DeserializeOwned + Valid>(v: &json::Value) -> Result<(), String> {
+    typed::<T>(v)?.valid()
+}
+
+/// Checks the column values of an added row and the `check` conditions of its relation.
+fn validate(prog: &Program, rel: &str, row: &Row) -> Result<(), String> {
+    let rel = prog.relation(rel).unwrap();
+    for (c, v) in rel.columns.iter().zip(row.iter()) {
+        typed!(c.ty, valid_as, &v.to_json()).map_err(|e| format!("{}.{}: {}", rel.name, c.name, e))?;
+    };
+    let vars: Vec<String> = rel.columns.iter().map(|c| c.name.clone()).collect();
+    for &(ref cond, ref msg) in &rel.checks {
+        if !eval(cond, &vars, row)?.to_bool()? {
+            return Err(format!("{}: {}", rel.name, msg));
+        };
+    };
+    Ok(())
+}
+
+/// The JSON form of a row: an array of columns, or the column of a one-column relation.
+fn row_json(prog: &Program, rel: &str, row: &Row) -> Result<json::Value, String> {
+    let rel = prog.relation(rel).unwrap();
+    let mut cols = Vec::new();
+    for (c, v) in rel.columns.iter().zip(row.iter()) {
+        cols.push(encode(&c.ty, v)?);
+    };
+    Ok(if cols.len() == 1 { cols.pop().unwrap() } else { json::Value::Array(cols) })
+}
+
+fn fact_json(prog: &Program, rel: &str, row: &Row) -> Result<json::Value, String> {
+    let mut m = json::Map::new();
+    m.insert(rel.to_string(), row_json(prog, rel, row)?);
+    Ok(json::Value::Object(m))
+}
+
+fn respond<T: Serialize>(resp: Response<T>) {
+    serde_json::to_writer(stdout(), &resp).unwrap();
+    stdout().flush().unwrap();
+}
+
+/// Evaluates the rules in `path` over the requests read from stdin, replicating as `config`
+/// says.
+pub fn main(path: String, args: Vec<String>, config: replica::Config) {
+    let prog = match load(&path) {
+        Ok(p)  => p,
+        Err(e) => {
+            eprintln!("{}:{}", path, e);
+            std::process::exit(-1);
+        }
+    };
+
+    execute_from_args!(args.into_iter(), move |worker| {
+        let probe = probe::Handle::new();
+        let mut probe1 = probe.clone();
+        let traces = compact::Traces::new();
+
+        let mut xaction : bool = false;
+
+        let rels = prog.enum_order();
+        let mut sets: HashMap<String, Rc<RefCell<HashSet<Row>>>> = HashMap::new();
+        let mut deltas: HashMap<String, Rc<RefCell<HashMap<Row, i8>>>> = HashMap::new();
+        let mut hists: HashMap<String, Rc<RefCell<History<Row>>>> = HashMap::new();
+        for r in &rels {
+            sets.insert(r.name.clone(), Rc::new(RefCell::new(HashSet::new())));
+            deltas.insert(r.name.clone(), Rc::new(RefCell::new(HashMap::new())));
+            hists.insert(r.name.clone(), Rc::new(RefCell::new(History::new())));
+        };
+
+        let mut handles = worker.dataflow::<u64,_,_>(|outer| {
+            let mut colls = HashMap::new();
+            let mut handles = HashMap::new();
+            for r in prog.dataflow_order().unwrap() {
+                let (h, mut coll) = outer.new_collection::<Row,isize>();
+                let (set, delta, hist) = (sets[&r.name].clone(), deltas[&r.name].clone(), hists[&r.name].clone());
+                if r.kind.is_input() {
+                    // written only by worker 0, which keeps them
+                    coll.inner.inspect(move |x| { xupd(&set, &delta, &(x.0), x.2); hist.borrow_mut().record(&(x.0), x.1, x.2) }).probe_with(&mut probe1);
+                };
+                for (i, rule) in prog.rules_for(&r.name).iter().enumerate() {
+                    let name = format!("{}#{} (line {})", r.name, i + 1, rule.line);
+                    coll = coll.concat(&profile::named(&rule_collection(rule, &colls, &traces), &name));
+                };
+                let coll = profile::named(&coll.distinct_held(&traces), &r.name);
+                if !r.kind.is_input() {
+                    let (set, hist) = (sets[&r.name].clone(), hists[&r.name].clone());
+                    coll.inner.inspect(move |x| { upd(&set, &(x.0), x.2); hist.borrow_mut().record(&(x.0), x.1, x.2) }).probe_with(&mut probe1);
+                };
+                colls.insert(r.name.clone(), coll);
+                handles.insert(r.name.clone(), h);
+            };
+            handles
+        });
+
+        // the facts of a derived relation stay with the worker that derived them, as with the
+        // compiled rules, and `gather!` asks every worker for its share
+        let names: Vec<String> = rels.iter().map(|r| r.name.clone()).collect();
+        let gathered = Rc::new(RefCell::new(Vec::new()));
+        let gathered1 = gathered.clone();
+        let gprobe = probe::Handle::new();
+        let mut gprobe1 = gprobe.clone();
+        let (gsets, ghists) = (sets.clone(), hists.clone());
+        let mut gather = worker.dataflow::<u64,_,_>(move |outer| {
+            let (handle, queries) = outer.new_collection::<(usize, bool),isize>();
+            queries.inner.broadcast().unary_stream(timely::dataflow::channels::pact::Pipeline, "Gather", move |input, output| {
+                input.for_each(|time, data| {
+                    let mut session = output.session(&time);
+                    for &((rel, history), _, _) in data.iter() {
+                        session.give(shard(&gsets[&names[rel]].borrow(), &ghists[&names[rel]].borrow(), history));
+                    };
+                });
+            }).exchange(|_| 0).inspect(move |x| gathered1.borrow_mut().push(x.clone())).probe_with(&mut gprobe1);
+            handle
+        });
+
+        // worker 0 serves the protocol, as with the compiled rules
+        if worker.index() != 0 {
+            drop(handles);
+            drop(gather);
+            while worker.step() {
+                if let Some(e) = probe.with_frontier(|f| f.iter().cloned().min()) { traces.compact(e) };
+            };
+            return;
+        };
+
+        let mut epoch = 0;
+        let mut queries = 0;
+        let mut steps: u64 = 0;
+        let mut started = Instant::now();
+        let mut commits = VecDeque::new();
+        let mut audit = Audit::new();
+        let mut txn = (TxnMeta::default(), 0);
+        let mut following = config.follow.is_some();
+        let mut followers = replica::Followers::new();
+        let events = match replica::events::<json::Value, Vec<(json::Value, i8)>>(config.follow.clone(), config.replicate.clone()) {
+            Ok(ev) => ev,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(-1);
+            }
+        };
+
+        for ev in events {
+            macro_rules! advance {
+                () => {{
+                    for h in handles.values_mut() {
+                        h.advance_to(epoch);
+                    };
+                    for h in handles.values_mut() {
+                        h.flush();
+                    };
+                    traces.compact(epoch);
+                }}
+            }
+
+            // the shards that every worker holds for query `$q`: see `gather`
+            macro_rules! gather {
+                ($q:expr) => {{
+                    gather.insert($q);
+                    queries += 1;
+                    gather.advance_to(queries);
+                    gather.flush();
+                    while gprobe.less_than(gather.time()) {
+                        worker.step();
+                    };
+                    gathered.borrow_mut().drain(..).collect::<Vec<String>>()
+                }}
+            }
+
+            // the facts of relation `$rel`, and its history if `$history`: those of an input
+            // relation are at hand, and those of a derived one are gathered
+            macro_rules! gathered {
+                ($rel:expr, $history:expr) => {{
+                    let rel: &str = $rel;
+                    if prog.relation(rel).unwrap().kind.is_input() {
+                        (sets[rel].clone(), hists[rel].clone())
+                    } else {
+                        let i = rels.iter().position(|r| r.name == rel).unwrap();
+                        let (set, hist) = merge_shards::<Row>(&gather!((i, $history)));
+                        (Rc::new(RefCell::new(set)), Rc::new(RefCell::new(hist)))
+                    }
+                }}
+            }
+
+            macro_rules! insert {
+                ($rel:expr, $v:expr) => {{
+                    let v = $v;
+                    if !gathered!($rel, false).0.borrow().contains(&v) {
+                        handles.get_mut($rel).unwrap().insert(v);
+                        epoch = epoch+1;
+                        advance!();
+                        while probe.less_than(handles[$rel].time()) {
+                            worker.step();
+                            steps += 1;
+                        };
+                    };
+                }}
+            }
+
+            macro_rules! remove {
+                ($rel:expr, $v:expr) => {{
+                    let v = $v;
+                    if gathered!($rel, false).0.borrow().contains(&v) {
+                        handles.get_mut($rel).unwrap().remove(v);
+                        epoch = epoch+1;
+                        advance!();
+                        while probe.less_than(handles[$rel].time()) {
+                            worker.step();
+                            steps += 1;
+                        };
+                    };
+                }}
+            }
+
+            macro_rules! delta_cleanup {
+                () => {{
+                    for d in deltas.values() {
+                        d.borrow_mut().clear();
+                    };
+                }}
+            }
+
+            macro_rules! start {
+                ($meta:expr) => {{
+                    respond(if xaction {
+                                Response::err(format!("transaction already in progress"))
+                            } else {
+                                match $meta {
+                                    Ok(meta) => {
+                                        delta_cleanup!();
+                                        xaction = true;
+                                        started = Instant::now();
+                                        txn = (meta, epoch);
+                                        Response::ok(())
+                                    },
+                                    Err(e)   => Response::err(e)
+                                }
+                            });
+                }}
+            }
+
+            // the changes since the last commit, as `[fact, weight]` pairs
+            macro_rules! changes {
+                () => {{
+                    let mut changes = Vec::new();
+                    for r in rels.iter().filter(|r| r.kind.is_input()) {
+                        for (k, v) in deltas[&r.name].borrow().iter().filter(|&(_, v)| *v != 0) {
+                            changes.push((fact_json(&prog, &r.name, k).unwrap(), *v));
+                        };
+                    };
+                    changes
+                }}
+            }
+
+            // sends the changes made since epoch `$from`, outside of a transaction, to the
+            // followers and records them in the audit log
+            macro_rules! ship {
+                ($from:expr) => {{
+                    let changes = changes!();
+                    delta_cleanup!();
+                    followers.send(epoch, &changes);
+                    if !changes.is_empty() { audit.record(TxnMeta::default(), ($from, epoch), changes) };
+                }}
+            }
+
+            // the committed input facts
+            macro_rules! snapshot {
+                () => {{
+                    let mut facts = Vec::new();
+                    for r in rels.iter().filter(|r| r.kind.is_input()) {
+                        let (d, rows) = (deltas[&r.name].borrow(), sets[&r.name].borrow());
+                        let removed = d.iter().filter(|&(_, v)| *v == -1).map(|(row, _)| row);
+                        for row in rows.iter().filter(|row| d.get(*row) != Some(&1)).chain(removed) {
+                            facts.push((fact_json(&prog, &r.name, row).unwrap(), 1i8));
+                        };
+                    };
+                    facts
+                }}
+            }
+
+            macro_rules! apply {
+                ($changes:expr) => {{
+                    for (f, w) in $changes {
+                        match fact(&prog, &f) {
+                            Ok((rel, row)) => if !prog.relation(&rel).unwrap().kind.is_input() {
+                                eprintln!("ignoring replicated change to derived fact {}", f);
+                            } else if w > 0 {
+                                insert!(&rel, row);
+                            } else {
+                                remove!(&rel, row);
+                            },
+                            Err(e)         => eprintln!("ignoring replicated change {}: {}", f, e)
+                        };
+                    };
+                    delta_cleanup!();
+                }}
+            }
+
+            // replaces the input facts with those of `$facts`, a snapshot of the primary's
+            macro_rules! resync {
+                ($facts:expr) => {{
+                    let facts: Vec<(json::Value, i8)> = $facts;
+                    let kept: HashSet<String> = facts.iter().map(|f| f.0.to_string()).collect();
+                    let stale: Vec<(json::Value, i8)> = snapshot!().into_iter().filter(|f| !kept.contains(&f.0.to_string())).map(|(f, _)| (f, -1)).collect();
+                    apply!(stale);
+                    apply!(facts);
+                }}
+            }
+
+            let val = match ev {
+                replica::Event::Request(r)      => r,
+                replica::Event::Follower(s)     => {
+                    if following { eprintln!("turning away a follower: not a primary until promoted") } else { followers.add(s, &snapshot!()) };
+                    continue
+                },
+                replica::Event::Snapshot(f)     => {
+                    if following { resync!(f) };
+                    continue
+                },
+                replica::Event::Changes(Ok(c))  => {
+                    if following { apply!(c) };
+                    continue
+                },
+                replica::Event::Changes(Err(e)) => {
+                    if following { eprintln!("{}", e) };
+                    continue
+                }
+            };
+            let (scope, req) = match val.and_then(|r| unscope(&prog, r)) {
+                            Ok(r)  => r,
+                            Err(e) => {
+                                respond(Response::<()>::err(e));
+                                continue;
+                            }
+                        };
+            // the facts of relation `$rel` visible in the request's namespace
+            macro_rules! visible {
+                ($rel:expr) => {{
+                    let namespaced = prog.relation($rel).unwrap().namespaced;
+                    let set = gathered!($rel, false).0;
+                    let rows: Vec<Row> = set.borrow().iter().filter(|row| !namespaced || scope.as_ref().map_or(true, |ns| row[0] == *ns)).cloned().collect();
+                    rows
+                }}
+            }
+
+            let (cmd, arg) = match req {
+                json::Value::String(ref s) => (s.clone(), json::Value::Null),
+                _ => match json_variant(&req) {
+                    Ok((k, a)) => (k.to_string(), a.clone()),
+                    Err(e)     => { respond(Response::<()>::err(e)); continue; }
+                }
+            };
+            if following && ["start", "start_with", "rollback", "commit", "add", "del"].contains(&cmd.as_str()) {
+                respond(Response::<()>::err(format!("{}: a follower is read-only until it is promoted", req)));
+                continue;
+            };
+            match (cmd.as_str(), arg) {
+                ("start", _)    => start!(Ok(TxnMeta::default())),
+                ("start_with", m) => start!(json::from_value::<TxnMeta>(m).map_err(|e| format!("start_with: {}", e))),
+                ("rollback", _) => {
+                    respond(if !xaction {
+                                Response::err(format!("no transaction in progress"))
+                            } else {
+                                for r in rels.iter().filter(|r| r.kind.is_input()) {
+                                    let mut d = deltas[&r.name].borrow().clone();
+                                    for (k, v) in d.drain() {
+                                        if v == 1 {
+                                            remove!(&r.name, k);
+                                        } else if v == -1 {
+                                            insert!(&r.name, k);
+                                        };
+                                    };
+                                };
+                                delta_cleanup!();
+                                xaction = false;
+                                Response::ok(())
+                            });
+                },
+                ("commit", _)   => {
+                    respond(if !xaction {
+                                Response::err(format!("no transaction in progress"))
+                            } else {
+                                let changes = changes!();
+                                delta_cleanup!();
+                                followers.send(epoch, &changes);
+                                xaction = false;
+                                commit_stats(&mut commits, epoch, started);
+                                audit.record(txn.0.clone(), (txn.1, epoch), changes.clone());
+                                Response::ok(changes)
+                            });
+                },
+                ("audit", json::Value::Null) => respond(match audit.query(None) {
+                    Ok(entries) => Response::ok(entries),
+                    Err(e)      => Response::err(e)
+                }),
+                ("audit", f)    => match fact(&prog, &f).and_then(|(rel, row)| fact_json(&prog, &rel, &row)) {
+                    Ok(f)  => respond(match audit.query(Some(&f)) {
+                        Ok(entries) => Response::ok(entries),
+                        Err(e)      => Response::err(e)
+                    }),
+                    Err(e) => respond(Response::<()>::err(e))
+                },
+                ("schema", _)   => respond(Response::ok(schema(&prog))),
+                ("promote", _)  => respond(if !following {
+                                       Response::err(format!("not a follower"))
+                                   } else {
+                                       following = false;
+                                       Response::ok(())
+                                   }),
+                ("profile", _)  => respond(match profile::report() {
+                                       Ok(p)  => Response::ok(p),
+                                       Err(e) => Response::err(e)
+                                   }),
+                ("stats", _)    => {
+                    let mut rows = BTreeMap::new();
+                    for r in &rels {
+                        rows.insert(r.name.clone(), gathered!(&r.name, false).0.borrow().len());
+                    };
+                    respond(Response::ok(Stats {
+                        epoch: epoch,
+                        steps: steps,
+                        commits: commits.iter().cloned().collect(),
+                        rows: rows,
+                        deltas: rels.iter().filter(|r| r.kind.is_input())
+                                    .map(|r| (r.name.clone(), deltas[&r.name].borrow().len())).collect()
+                    }));
+                },
+                ("add", f)      => match write(&prog, &req, &f).and_then(|(rel, row)| { validate(&prog, &rel, &row)?; Ok((rel, row)) }) {
+                    Ok((rel, row)) => { let from = epoch; insert!(&rel, row); if !xaction { ship!(from) }; respond(Response::ok(())) },
+                    Err(e)         => respond(Response::<()>::err(e))
+                },
+                ("del", f)      => match write(&prog, &req, &f) {
+                    Ok((rel, row)) => { let from = epoch; remove!(&rel, row); if !xaction { ship!(from) }; respond(Response::ok(())) },
+                    Err(e)         => respond(Response::<()>::err(e))
+                },
+                ("chk", json::Value::String(ref rel)) if sets.contains_key(rel) => {
+                    respond(Response::ok(!visible!(rel).is_empty()));
+                },
+                ("enm", json::Value::String(ref rel)) if sets.contains_key(rel) => {
+                    let rows: Result<Vec<_>, _> = visible!(rel).iter().map(|row| row_json(&prog, rel, row)).collect();
+                    match rows {
+                        Ok(rows) => respond(Response::ok(rows)),
+                        Err(e)   => respond(Response::<()>::err(e))
+                    }
+                },
+                ("enm_at", json::Value::Array(ref a)) if a.len() == 2 && a[1].is_u64()
+                                                         && a[0].as_str().map_or(false, |r| sets.contains_key(r)) => {
+                    let rel = a[0].as_str().unwrap();
+                    let (set, hist) = gathered!(rel, true);
+                    let rows = hist.borrow().at(&set.borrow(), a[1].as_u64().unwrap(), epoch)
+                                   .and_then(|set| set.iter().map(|row| row_json(&prog, rel, row)).collect::<Result<Vec<_>, _>>());
+                    match rows {
+                        Ok(rows) => respond(Response::ok(rows)),
+                        Err(e)   => respond(Response::<()>::err(e))
+                    }
+                },
+                ("diff", json::Value::Array(ref a)) if a.len() == 3 && a[1].is_u64() && a[2].is_u64()
+                                                       && a[0].as_str().map_or(false, |r| sets.contains_key(r)) => {
+                    let rel = a[0].as_str().unwrap();
+                    let rows = |v: Vec<Row>| v.iter().map(|row| row_json(&prog, rel, row)).collect::<Result<Vec<_>, _>>();
+                    let (set, hist) = gathered!(rel, true);
+                    let diff = hist.borrow().diff(&set.borrow(), a[1].as_u64().unwrap(), a[2].as_u64().unwrap(), epoch)
+                                   .and_then(|d| Ok(HistoryDiff { added: rows(d.added)?, removed: rows(d.removed)? }));
+                    match diff {
+                        Ok(d)  => respond(Response::ok(d)),
+                        Err(e) => respond(Response::<()>::err(e))
+                    }
+                },
+                (cmd, arg)      => respond(Response::<()>::err(format!("invalid request {} {}", cmd, arg)))
+            };
+        };
+    }).unwrap();
+}
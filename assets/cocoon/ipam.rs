@@ -0,0 +1,77 @@
+// Address management for the generated controller. `build.rs` pastes this macro into the request
+// loop, after `insert!`, `remove!`, `gathered!` and `ipam_dirty!`, so it can use them, the `_r*`
+// shadow sets of input relations and the allocation cursors `ip_next` and `mac_next`, which it
+// declares before the loop. The derived relations read here are gathered from the workers.
+
+// Hands out addresses to ports in `LPortDynIPPending`, after releasing allocations in
+// `LPortDynIPStale`; an allocation becomes stale when its port goes away or when the address
+// is taken statically, so the port gets a new one here. Each switch allocates next-fit from a
+// cursor just past its last allocation, wrapping around its subnet, so released addresses are
+// not reused until the rest of the subnet has been. Requests still pending afterwards have
+// exhausted their switch's subnet and show up in `LSwitchIPExhausted`.
+//
+// MACs are handled the same way through `LPortDynMACPending` and `LPortDynMACStale`,
+// drawing from the tenant's OUI in `DynMACPrefix` (the smallest one, if several are given,
+// and 0a:00:00 if none is), with one cursor per tenant. Addresses are unique within a tenant.
+//
+// Nothing is done unless one of the relations in `IPAM_WATCHED` changed since the last time, so
+// updates that do not touch addresses do not pay for the ports that are stuck pending.
+macro_rules! ipam {
+    () => {{
+        if ipam_dirty!() {
+            let (stale, _) = gathered!(LPortDynIPStale, false);
+            for (tenant, lswitch, lport, ip) in stale {
+                remove!(_LPortDynIPAlloc, _rLPortDynIPAlloc, (tenant, lswitch, lport, ip));
+            };
+            let mut pending: Vec<_> = gathered!(LPortDynIPPending, false).0.into_iter().collect();
+            pending.sort();
+            let (mut inuse, _) = gathered!(LSwitchIPInUse, false);
+            let mut full = HashSet::new();
+            for (tenant, lswitch, lport, subnet) in pending {
+                if full.contains(&(tenant, lswitch)) { continue };
+                let hosts = subnet.hosts();
+                let start = match ip_next.get(&(tenant, lswitch)) {
+                    Some(&ip) if ip >= hosts.start && ip < hosts.end => ip,
+                    _ => hosts.start
+                };
+                let free = (start .. hosts.end).chain(hosts.start .. start).find(|&ip| !inuse.contains(&(tenant, lswitch, ip)));
+                match free {
+                    Some(ip) => {
+                        ip_next.insert((tenant, lswitch), ip + 1);
+                        inuse.insert((tenant, lswitch, ip));
+                        insert!(_LPortDynIPAlloc, _rLPortDynIPAlloc, (tenant, lswitch, lport, ip));
+                    },
+                    None => { full.insert((tenant, lswitch)); }
+                };
+            };
+            let (stale, _) = gathered!(LPortDynMACStale, false);
+            for (tenant, lport, mac) in stale {
+                remove!(_LPortDynMACAlloc, _rLPortDynMACAlloc, (tenant, lport, mac));
+            };
+            let mut pending: Vec<_> = gathered!(LPortDynMACPending, false).0.into_iter().collect();
+            pending.sort();
+            let (mut inuse, _) = gathered!(MACInUse, false);
+            let mut full = HashSet::new();
+            for (tenant, lport) in pending {
+                if full.contains(&tenant) { continue };
+                let prefix = _rDynMACPrefix.borrow().iter().filter(|&&(t, _)| t == tenant).map(|&(_, p)| p).min();
+                let prefix = (prefix.unwrap_or(0x0a0000) as u64 & 0xffffff) << 24;
+                let start = match mac_next.get(&tenant) {
+                    Some(&n) if n >= 1 && n < 0x1000000 => n,
+                    _ => 1
+                };
+                let free = (start .. 0x1000000).chain(1 .. start).find(|&n| !inuse.contains(&(tenant, prefix | n)));
+                match free {
+                    Some(n) => {
+                        mac_next.insert(tenant, n + 1);
+                        inuse.insert((tenant, prefix | n));
+                        insert!(_LPortDynMACAlloc, _rLPortDynMACAlloc, (tenant, lport, prefix | n));
+                    },
+                    None => { full.insert(tenant); }
+                };
+            };
+            // forgets the changes made here
+            ipam_dirty!();
+        };
+    }}
+}
@@ -0,0 +1,186 @@
+//! Profiling by rule.
+//!
+//! Each rule branch, and the `distinct` of each relation, ends in a pass-through operator named
+//! after it (`Relation#2 (line 31)`, `Relation`), so that timely's log can be read in terms of
+//! rules: the operators of a branch are those created after the previous named one. With the
+//! `logging` feature, `execute_from_args!` hands each worker's log to `Log`, and
+//! `Request::profile` reports, for each named operator, the records it received and the time
+//! timely spent scheduling the operators of its branch. Time is spent in rule evaluation
+//! proper, not in maintaining the arrangements of joins, as those are shared.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+
+use timely::dataflow::*;
+use timely::dataflow::channels::pact::Pipeline;
+use timely::dataflow::operators::Unary;
+
+use differential_dataflow::{Collection, Data};
+use differential_dataflow::lattice::Lattice;
+
+#[cfg(feature = "logging")]
+pub use self::logging::logger_config;
+
+/// What the named operator of a rule branch, or of a relation, has seen.
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct RuleProfile {
+    /// Records received by the named operator, counting additions and retractions alike.
+    pub records: u64,
+    /// Seconds for which the operators of the branch were scheduled.
+    pub secs: f64
+}
+
+thread_local! {
+    /// The names given to operators by `named` on this worker.
+    static NAMES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// `timely::execute_from_args`, recording each worker's log for `report` in builds with the
+/// `logging` feature.
+#[cfg(feature = "logging")]
+macro_rules! execute_from_args {
+    ($args:expr, $func:expr) => { ::timely::execute_from_args_logging($args, ::profile::logger_config(), $func) }
+}
+
+#[cfg(not(feature = "logging"))]
+macro_rules! execute_from_args {
+    ($args:expr, $func:expr) => { ::timely::execute_from_args($args, $func) }
+}
+
+/// Passes `coll` through an operator called `name`.
+pub fn named<G: Scope, D: Data>(coll: &Collection<G, D, isize>, name: &str) -> Collection<G, D, isize>
+where G::Timestamp: Lattice+Ord {
+    NAMES.with(|n| n.borrow_mut().insert(name.to_string()));
+    Collection::new(coll.inner.unary_stream(Pipeline, name, |input, output| {
+        input.for_each(|time, data| {
+            output.session(&time).give_content(data);
+        });
+    }))
+}
+
+#[cfg(feature = "logging")]
+mod logging {
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    use timely::dataflow::operators::capture::{Event, EventPusher};
+    use timely::logging::{EventPusherTee, LogMessage, LoggerConfig, StartStop, TimelyEvent};
+    use timely::progress::nested::product::Product;
+    use timely::progress::timestamp::RootTimestamp;
+
+    use super::{RuleProfile, NAMES};
+
+    /// What this worker's log has said about its operators.
+    #[derive(Default)]
+    struct Log {
+        /// The address and name of each operator, by id, that is, in order of creation.
+        operators: BTreeMap<usize, (Vec<usize>, String)>,
+        /// The address of the operator each channel leads to.
+        channels: HashMap<usize, Vec<usize>>,
+        /// When each operator that is running was scheduled, in nanoseconds.
+        started: HashMap<usize, u64>,
+        /// Nanoseconds for which each operator has run.
+        busy: HashMap<usize, u64>,
+        /// Records received by each operator, by address.
+        received: HashMap<Vec<usize>, u64>
+    }
+
+    thread_local! {
+        static LOG: RefCell<Log> = RefCell::new(Log::default());
+    }
+
+    impl Log {
+        fn record(&mut self, time: u64, event: TimelyEvent) {
+            match event {
+                TimelyEvent::Operates(e) => { self.operators.insert(e.id, (e.addr, e.name)); },
+                TimelyEvent::Channels(e) => {
+                    let mut target = e.scope_addr;
+                    target.push(e.target.0);
+                    self.channels.insert(e.id, target);
+                },
+                TimelyEvent::Messages(e) => if !e.is_send {
+                    if let Some(target) = self.channels.get(&e.channel) {
+                        *self.received.entry(target.clone()).or_insert(0) += e.length as u64;
+                    };
+                },
+                TimelyEvent::Schedule(e) => match e.start_stop {
+                    StartStop::Start       => { self.started.insert(e.id, time); },
+                    StartStop::Stop { .. } => if let Some(start) = self.started.remove(&e.id) {
+                        *self.busy.entry(e.id).or_insert(0) += time.saturating_sub(start);
+                    }
+                },
+                _ => ()
+            }
+        }
+
+        /// The profile of each named operator: the records it received, and the time of the
+        /// operators created since the previous one.
+        fn report(&self) -> BTreeMap<String, RuleProfile> {
+            let names = NAMES.with(|n| n.borrow().clone());
+            let mut report = BTreeMap::new();
+            let mut nanos = 0;
+            for (id, &(ref addr, ref name)) in &self.operators {
+                nanos += self.busy.get(id).cloned().unwrap_or(0);
+                if names.contains(name) {
+                    let r = report.entry(name.clone()).or_insert_with(RuleProfile::default);
+                    r.records += self.received.get(addr).cloned().unwrap_or(0);
+                    r.secs += nanos as f64 * 1e-9;
+                    nanos = 0;
+                };
+            };
+            report
+        }
+    }
+
+    /// Hands the events of a worker's log, which it flushes on its own thread, to `LOG`.
+    struct Recorder;
+
+    impl EventPusher<Product<RootTimestamp, u64>, LogMessage> for Recorder {
+        fn push(&mut self, event: Event<Product<RootTimestamp, u64>, LogMessage>) {
+            if let Event::Messages(_, messages) = event {
+                LOG.with(|log| {
+                    let mut log = log.borrow_mut();
+                    for (time, _, e) in messages {
+                        log.record(time, e);
+                    };
+                });
+            };
+        }
+    }
+
+    /// Subscribes `Recorder` to the timely log of every worker.
+    pub fn logger_config() -> LoggerConfig {
+        LoggerConfig {
+            timely_logging: Arc::new(|_| {
+                let tee = Arc::new(EventPusherTee::new());
+                tee.subscribe(Box::new(Recorder));
+                tee
+            }),
+            communication_logging: Arc::new(|_| Arc::new(EventPusherTee::new()))
+        }
+    }
+
+    pub fn report() -> BTreeMap<String, RuleProfile> {
+        LOG.with(|log| log.borrow().report())
+    }
+}
+
+/// The profile of this worker, by operator name, as of the last time timely flushed its log.
+pub fn report() -> Result<BTreeMap<String, RuleProfile>, String> {
+    if cfg!(feature = "logging") {
+        Ok(report_logged())
+    } else {
+        Err(format!("profiling needs a build with the logging feature"))
+    }
+}
+
+#[cfg(feature = "logging")]
+fn report_logged() -> BTreeMap<String, RuleProfile> {
+    logging::report()
+}
+
+#[cfg(not(feature = "logging"))]
+fn report_logged() -> BTreeMap<String, RuleProfile> {
+    BTreeMap::new()
+}
@@ -0,0 +1,194 @@
+//! Hot-standby replication of committed input facts.
+//!
+//! A primary started with `--replicate ADDR` listens on ADDR. A follower started with `--follow
+//! ADDR` connects to it and is sent a `Message::Snapshot` of the primary's committed input
+//! facts, then a `Message::Changes` batch of `[fact, 1]` and `[fact, -1]` pairs each time
+//! changes are committed (or, outside a transaction, made), one JSON message per line. Each
+//! message carries the epoch of the commit it reflects, and each batch the epoch of the one
+//! before, so a follower that misses a batch notices, and reconnects for a new snapshot, as it
+//! does when the primary drops it for falling too far behind. The follower applies what it is
+//! sent to its own dataflow, without passing it on, and serves reads only, until
+//! `Request::promote` makes it stop following and accept writes and followers of its own.
+//!
+//! The primary writes to each follower on a thread of its own, through a queue of at most
+//! `QUEUE` messages, so a slow follower does not hold back requests. The request loop waits on
+//! `events`, which merges the requests read from stdin with the followers connecting and the
+//! messages arriving from the primary.
+
+use std::io::{stdin, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender, SyncSender, TrySendError};
+use std::thread;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json as json;
+
+use super::Requests;
+
+/// The replication flags.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The address of the primary to follow.
+    pub follow: Option<String>,
+    /// The address to listen for followers at.
+    pub replicate: Option<String>
+}
+
+impl Config {
+    /// Takes `--follow ADDR` and `--replicate ADDR` out of `args`.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Config, String> {
+        Ok(Config { follow: flag(args, "--follow")?, replicate: flag(args, "--replicate")? })
+    }
+}
+
+fn flag(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|a| a == name) {
+        Some(i) if i + 1 < args.len() => {
+            let v = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(v))
+        },
+        Some(_) => Err(format!("{}: missing address", name)),
+        None    => Ok(None)
+    }
+}
+
+/// How many messages a follower may fall behind by before the primary drops it.
+const QUEUE: usize = 1024;
+
+/// What a primary sends its followers.
+#[derive(Serialize, Deserialize)]
+pub enum Message<C> {
+    /// The committed input facts as of the commit at an epoch.
+    Snapshot(u64, C),
+    /// The changes committed at the second epoch, following those committed at the first.
+    Changes(u64, u64, C)
+}
+
+/// Something for the request loop to act on.
+pub enum Event<T, C> {
+    /// A request from stdin, or the error reading it.
+    Request(Result<T, String>),
+    /// A follower connected to this primary.
+    Follower(TcpStream),
+    /// The committed input facts of the primary this process follows, which replace its own.
+    Snapshot(C),
+    /// Changes committed on the primary this process follows.
+    Changes(Result<C, String>)
+}
+
+/// Reads requests from stdin, and the messages from the primary at `follow` or the followers
+/// connecting at `replicate`, on threads of their own. The events end with stdin.
+pub fn events<T, C>(follow: Option<String>, replicate: Option<String>) -> Result<Receiver<Event<T, C>>, String>
+where T: DeserializeOwned+Send+'static, C: DeserializeOwned+Send+'static {
+    let (send, recv) = channel();
+    if let Some(addr) = follow {
+        let stream = TcpStream::connect(&addr[..]).map_err(|e| format!("--follow {}: {}", addr, e))?;
+        let send = send.clone();
+        thread::spawn(move || messages(addr, stream, send));
+    };
+    if let Some(addr) = replicate {
+        let listener = TcpListener::bind(&addr[..]).map_err(|e| format!("--replicate {}: {}", addr, e))?;
+        let send = send.clone();
+        thread::spawn(move || {
+            for s in listener.incoming() {
+                match s {
+                    Ok(s)  => if send.send(Event::Follower(s)).is_err() { break },
+                    Err(e) => eprintln!("--replicate: {}", e)
+                }
+            }
+        });
+    };
+    thread::spawn(move || {
+        for r in Requests::new(stdin()) {
+            if send.send(Event::Request(r)).is_err() { break };
+        }
+    });
+    Ok(recv)
+}
+
+/// Passes on the messages from the primary at `addr`, in sequence, reconnecting for a snapshot
+/// whenever one is missing or the primary drops the connection.
+fn messages<T, C: DeserializeOwned>(addr: String, mut stream: TcpStream, send: Sender<Event<T, C>>) {
+    // the epoch of the last commit passed on
+    let mut epoch = None;
+    loop {
+        for line in BufReader::new(stream).lines() {
+            let m = line.map_err(|e| e.to_string())
+                        .and_then(|l| json::from_str(&l).map_err(|e| format!("bad message from the primary: {}", e)));
+            let event = match m {
+                Ok(Message::Snapshot(e, facts))  => { epoch = Some(e); Event::Snapshot(facts) },
+                Ok(Message::Changes(from, e, c)) => if epoch == Some(from) {
+                    epoch = Some(e);
+                    Event::Changes(Ok(c))
+                } else {
+                    eprintln!("--follow {}: missed the changes after epoch {}; asking for a snapshot", addr, epoch.unwrap_or(0));
+                    break
+                },
+                Err(e)                           => {
+                    let _ = send.send(Event::Changes(Err(e)));
+                    return
+                }
+            };
+            if send.send(event).is_err() { return };
+        };
+        epoch = None;
+        stream = match TcpStream::connect(&addr[..]) {
+            Ok(s)  => s,
+            Err(e) => {
+                let _ = send.send(Event::Changes(Err(format!("the primary closed the replication stream: {}", e))));
+                return
+            }
+        };
+    }
+}
+
+/// The followers of a primary.
+pub struct Followers {
+    queues: Vec<SyncSender<Arc<Vec<u8>>>>,
+    /// The epoch of the last commit sent.
+    epoch: u64
+}
+
+impl Followers {
+    pub fn new() -> Followers {
+        Followers { queues: Vec::new(), epoch: 0 }
+    }
+
+    /// Adds a follower, sending it `snapshot`, the committed input facts.
+    pub fn add<S: Serialize>(&mut self, mut stream: TcpStream, snapshot: &S) {
+        let (queue, lines) = sync_channel::<Arc<Vec<u8>>>(QUEUE);
+        thread::spawn(move || {
+            for line in lines {
+                if let Err(e) = stream.write_all(&line) {
+                    eprintln!("dropping follower: {}", e);
+                    return
+                };
+            }
+        });
+        queue.send(line(&Message::Snapshot(self.epoch, snapshot))).unwrap();
+        self.queues.push(queue);
+    }
+
+    /// Sends `changes`, committed at `epoch`, to every follower, dropping those that have
+    /// fallen too far behind or gone.
+    pub fn send<S: Serialize>(&mut self, epoch: u64, changes: &S) {
+        if !self.queues.is_empty() {
+            let line = line(&Message::Changes(self.epoch, epoch, changes));
+            self.queues.retain(|q| match q.try_send(line.clone()) {
+                Ok(())                             => true,
+                Err(TrySendError::Full(_))         => { eprintln!("dropping follower: {} messages behind", QUEUE); false },
+                Err(TrySendError::Disconnected(_)) => false
+            });
+        };
+        self.epoch = epoch;
+    }
+}
+
+fn line<S: Serialize>(x: &S) -> Arc<Vec<u8>> {
+    let mut line = json::to_vec(x).unwrap();
+    line.push(b'\n');
+    Arc::new(line)
+}
@@ -0,0 +1,39 @@
+//! Addresses in `--text-addrs` mode: text forms are accepted and emitted, and the structured forms
+//! still are accepted, and emitted for values that have no text form.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::Ovn;
+
+const TENANT: u32 = 1;
+
+#[test]
+fn accepts_structured_macs_over_48_bits() {
+    let mut ovn = Ovn::spawn(&["--text-addrs"]);
+    ovn.add("LogicalSwitchPortMAC", row![TENANT, 1, 0x0200_0000_0001u64]).unwrap();
+    ovn.add("LogicalSwitchPortMAC", row![TENANT, 1, 0x1_0000_0000_0000u64]).unwrap();
+    assert_eq!(ovn.rows("LogicalSwitchPortMAC"), vec![row![TENANT, 1, "02:00:00:00:00:01"],
+                                                      row![TENANT, 1, 0x1_0000_0000_0000u64]]);
+    ovn.del("LogicalSwitchPortMAC", row![TENANT, 1, "02:00:00:00:00:01"]).unwrap();
+    ovn.del("LogicalSwitchPortMAC", row![TENANT, 1, 0x1_0000_0000_0000u64]).unwrap();
+    assert_eq!(ovn.rows("LogicalSwitchPortMAC"), Vec::<json::Value>::new());
+}
+
+#[test]
+fn accepts_structured_subnets() {
+    let mut ovn = Ovn::spawn(&["--text-addrs"]);
+    let cidr: json::Value = json::from_str(r#"{"IP4Subnet": {"addr": 167772160, "mask": 4294967040}}"#).unwrap();
+    let opts: json::Value = json::from_str(&format!(r#"{{"DHCP4Options": {{"cidr": {}, "server_id": 167772161,
+                                                       "server_mac": 2199023255553, "router": 167772161,
+                                                       "lease_time": 3600}}}}"#, cidr)).unwrap();
+    ovn.add("DHCPv4Options", row![TENANT, 1, opts]).unwrap();
+    let text: json::Value = json::from_str(r#"{"DHCP4Options": {"cidr": "10.0.0.0/24", "server_id": "10.0.0.1",
+                                              "server_mac": "02:00:00:00:00:01", "router": "10.0.0.1",
+                                              "lease_time": 3600}}"#).unwrap();
+    assert_eq!(ovn.rows("DHCPv4Options"), vec![row![TENANT, 1, text]]);
+}
@@ -0,0 +1,88 @@
+//! The audit log of `Request::audit`: what it records and for how long.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::{obj, Ovn};
+
+const TENANT: u32 = 1;
+
+fn net(id: u64) -> json::Value {
+    row![TENANT, id, format!("pnet{}", id)]
+}
+
+fn audit(ovn: &mut Ovn) -> Vec<json::Value> {
+    match ovn.request(obj("audit", json::Value::Null)).unwrap() {
+        json::Value::Array(entries) => entries,
+        v => panic!("audit: not a list: {}", v)
+    }
+}
+
+/// The changes of an entry, as `(id, weight)` pairs of `PhysicalNetwork` facts.
+fn changes(entry: &json::Value) -> Vec<(u64, i64)> {
+    let mut cs: Vec<_> = entry["changes"].as_array().unwrap().iter()
+        .map(|c| (c[0]["PhysicalNetwork"][1].as_u64().unwrap(), c[1].as_i64().unwrap()))
+        .collect();
+    cs.sort();
+    cs
+}
+
+#[test]
+fn records_changes_outside_of_transactions() {
+    let mut ovn = Ovn::spawn(&[]);
+    ovn.add("PhysicalNetwork", net(1)).unwrap();
+    ovn.add("PhysicalNetwork", net(1)).unwrap();
+    ovn.del("PhysicalNetwork", net(1)).unwrap();
+    ovn.del("PhysicalNetwork", net(2)).unwrap();
+    let entries = audit(&mut ovn);
+    assert_eq!(entries.iter().map(changes).collect::<Vec<_>>(), vec![vec![(1, 1)], vec![(1, -1)]]);
+    assert_eq!(entries[0]["meta"]["client"], json::Value::Null);
+    let epochs: Vec<(u64, u64)> = entries.iter().map(|e| (e["epochs"][0].as_u64().unwrap(), e["epochs"][1].as_u64().unwrap())).collect();
+    assert!(epochs[0].0 < epochs[0].1 && epochs[0].1 == epochs[1].0 && epochs[1].0 < epochs[1].1, "{:?}", epochs);
+}
+
+#[test]
+fn records_transactions_as_one_entry() {
+    let mut ovn = Ovn::spawn(&[]);
+    ovn.add("PhysicalNetwork", net(1)).unwrap();
+    let mut meta = json::Map::new();
+    meta.insert("client".to_string(), json::Value::from("test"));
+    ovn.request(obj("start_with", json::Value::Object(meta))).unwrap();
+    ovn.add("PhysicalNetwork", net(2)).unwrap();
+    ovn.del("PhysicalNetwork", net(1)).unwrap();
+    ovn.request(json::Value::from("commit")).unwrap();
+    let entries = audit(&mut ovn);
+    assert_eq!(entries.iter().map(changes).collect::<Vec<_>>(), vec![vec![(1, 1)], vec![(1, -1), (2, 1)]]);
+    assert_eq!(entries[1]["meta"]["client"], json::Value::from("test"));
+}
+
+#[test]
+fn keeps_the_last_commits() {
+    let mut ovn = Ovn::spawn(&["--audit", "2"]);
+    for id in 1..5 {
+        ovn.add("PhysicalNetwork", net(id)).unwrap();
+    };
+    assert_eq!(audit(&mut ovn).iter().map(changes).collect::<Vec<_>>(), vec![vec![(3, 1)], vec![(4, 1)]]);
+}
+
+/// The window is counted in commits, so `--history` does not shorten it.
+#[test]
+fn keeps_the_log_whatever_the_history() {
+    let mut ovn = Ovn::spawn(&["--history", "1"]);
+    for id in 1..5 {
+        ovn.add("PhysicalNetwork", net(id)).unwrap();
+    };
+    assert_eq!(audit(&mut ovn).len(), 4);
+}
+
+#[test]
+fn reports_that_auditing_is_disabled() {
+    let mut ovn = Ovn::spawn(&["--audit", "0"]);
+    ovn.add("PhysicalNetwork", net(1)).unwrap();
+    let e = ovn.request(obj("audit", json::Value::Null)).unwrap_err();
+    assert!(e.contains("auditing is disabled"), "{}", e);
+}
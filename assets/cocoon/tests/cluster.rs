@@ -0,0 +1,93 @@
+//! `ovn` as a cluster of processes over loopback, against a single process: facts of derived
+//! relations stay with the worker that derives them and are gathered by the front end.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::{obj, Cluster, Ovn};
+
+const TENANT: u32 = 1;
+
+/// Chassis with tunnels, and two switches with ports asking for addresses.
+fn build(ovn: &mut Ovn) {
+    for c in 1..4u32 {
+        ovn.add("Chassis", row![c, false, format!("hv{}", c), format!("192.168.0.{}", c)]).unwrap();
+        ovn.add("TunnelPort", row![c, 1, c, 0xc0a8_0000u32 | c]).unwrap();
+        ovn.add("ChassisEncap", row![c, "EncapGeneve", 1]).unwrap();
+    };
+    ovn.add("ChassisEncap", row![3, "EncapVXLAN", 1]).unwrap();
+    for s in 1..3u64 {
+        ovn.add("LogicalSwitch", row![TENANT, s, "LSwitchRegular", format!("ls{}", s), format!("10.0.{}.0/24", s)]).unwrap();
+    };
+    for p in 1..9u64 {
+        ovn.add("LogicalSwitchPort", row![TENANT, p, 1 + p % 2, "LPortVM", format!("lsp{}", p), true,
+                                          "NoDHCP4Options", "NoDHCP6Options", false, 0]).unwrap();
+        ovn.add("LogicalSwitchPortDynAddr", row![TENANT, p, p, 0x0200_0000_0000 | p, "NoIPAddr"]).unwrap();
+    };
+    ovn.add("LogicalSwitchPortMAC", row![TENANT, 1, 0x0200_0000_0101u64]).unwrap();
+}
+
+/// Takes some of it down again.
+fn shrink(ovn: &mut Ovn) {
+    ovn.del("LogicalSwitchPortDynAddr", row![TENANT, 3, 3, 0x0200_0000_0003u64, "NoIPAddr"]).unwrap();
+    ovn.del("ChassisEncap", row![3, "EncapGeneve", 1]).unwrap();
+    ovn.del("Chassis", row![2, false, "hv2", "192.168.0.2"]).unwrap();
+}
+
+const RELATIONS: &[&str] = &["LPortDynIPAlloc", "LPortDynMACAlloc", "LPortDynIP", "LPortMAC", "LSwitchIPInUse",
+                             "MACInUse", "TunnelFromTo", "TunnelNoEncap", "LogicalSwitchPort"];
+
+/// What `ovn` tells of its relations: the facts of some, and the number of facts of all.
+fn state(ovn: &mut Ovn) -> Vec<json::Value> {
+    let mut state: Vec<json::Value> = RELATIONS.iter().map(|rel| json::Value::Array(ovn.rows(rel))).collect();
+    let stats = ovn.send("\"stats\"").unwrap();
+    state.push(stats["rows"].clone());
+    for &rel in RELATIONS {
+        state.push(ovn.request(obj("chk", json::Value::from(rel))).unwrap());
+    };
+    state
+}
+
+/// The facts added to and removed from `rel` since `from`, sorted.
+fn diff(ovn: &mut Ovn, rel: &str, from: u64) -> json::Value {
+    let epoch = ovn.send("\"stats\"").unwrap()["epoch"].clone();
+    let mut d = ovn.request(obj("diff", json::Value::Array(vec![json::Value::from(rel), json::Value::from(from), epoch])))
+                   .unwrap_or_else(|e| panic!("diff {}: {}", rel, e));
+    for k in &["added", "removed"] {
+        if let Some(rows) = d[*k].as_array_mut() {
+            rows.sort_by_key(|r| r.to_string());
+        };
+    };
+    d
+}
+
+fn agree(args: &[&str], port: u16) {
+    let mut single = Ovn::spawn(args);
+    let mut cluster = Cluster::spawn(2, 2, port, args);
+    build(&mut single);
+    build(&mut cluster.ovn);
+    assert_eq!(state(&mut cluster.ovn), state(&mut single));
+    assert_eq!(single.rows("TunnelFromTo").len(), 6);
+
+    let built = single.send("\"stats\"").unwrap()["epoch"].as_u64().unwrap();
+    shrink(&mut single);
+    shrink(&mut cluster.ovn);
+    assert_eq!(state(&mut cluster.ovn), state(&mut single));
+    for &rel in &["LSwitchIPInUse", "TunnelFromTo", "LPortDynIPAlloc"] {
+        assert_eq!(diff(&mut cluster.ovn, rel, built), diff(&mut single, rel, built), "{}", rel);
+    };
+}
+
+#[test]
+fn two_processes_agree_with_one() {
+    agree(&["--history", "1000"], 21010);
+}
+
+#[test]
+fn two_processes_interpreting_the_rules_agree_with_one() {
+    agree(&["--history", "1000", "--rules", concat!(env!("CARGO_MANIFEST_DIR"), "/ovn.dl")], 21020);
+}
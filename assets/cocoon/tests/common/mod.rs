@@ -0,0 +1,130 @@
+//! Drives an `ovn` process over its pipes, for the tests in this directory.
+
+#![allow(dead_code)]
+
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json as json;
+
+/// A JSON array of the values, for a fact's columns; expects `serde_json` as `json`.
+macro_rules! row {
+    ($($v:expr),*) => { json::Value::Array(vec![$(json::Value::from($v)),*]) }
+}
+
+/// A running `ovn`, killed when dropped.
+pub struct Ovn {
+    child: Child,
+    stdin: ChildStdin,
+    responses: json::StreamDeserializer<'static, json::de::IoRead<BufReader<ChildStdout>>, json::Value>
+}
+
+impl Ovn {
+    /// Starts the `ovn` binary of this package with `args`.
+    pub fn spawn(args: &[&str]) -> Ovn {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ovn")).args(args)
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()
+            .unwrap_or_else(|e| panic!("{}: {}", env!("CARGO_BIN_EXE_ovn"), e));
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ovn { child: child, stdin: stdin, responses: json::Deserializer::from_reader(stdout).into_iter() }
+    }
+
+    /// Sends one request line and returns the `ok` value of the response, or its `err`.
+    pub fn send(&mut self, line: &str) -> Result<json::Value, String> {
+        writeln!(self.stdin, "{}", line).unwrap();
+        self.stdin.flush().unwrap();
+        let resp = match self.responses.next() {
+            Some(Ok(resp)) => resp,
+            Some(Err(e))   => panic!("bad response to {}: {}", line, e),
+            None           => panic!("ovn exited on {}", line)
+        };
+        match (resp.get("ok").cloned(), resp.get("err")) {
+            (Some(v), _) => Ok(v),
+            (_, Some(e)) => Err(e.as_str().map_or_else(|| e.to_string(), String::from)),
+            _            => panic!("response to {} is neither ok nor err: {}", line, resp)
+        }
+    }
+
+    pub fn request(&mut self, req: json::Value) -> Result<json::Value, String> {
+        self.send(&req.to_string())
+    }
+
+    pub fn add(&mut self, rel: &str, row: json::Value) -> Result<(), String> {
+        self.request(obj("add", obj(rel, row))).map(|_| ())
+    }
+
+    pub fn del(&mut self, rel: &str, row: json::Value) -> Result<(), String> {
+        self.request(obj("del", obj(rel, row))).map(|_| ())
+    }
+
+    /// The rows of `rel`, sorted.
+    pub fn rows(&mut self, rel: &str) -> Vec<json::Value> {
+        let rows = self.request(obj("enm", json::Value::from(rel))).unwrap_or_else(|e| panic!("enm {}: {}", rel, e));
+        let mut rows = match rows {
+            json::Value::Array(rows) => rows,
+            v => panic!("enm {}: not a list: {}", rel, v)
+        };
+        rows.sort_by_key(|r| r.to_string());
+        rows
+    }
+}
+
+/// `{"key": v}`, the shape of requests and of struct variants.
+pub fn obj(key: &str, v: json::Value) -> json::Value {
+    let mut m = json::Map::new();
+    m.insert(key.to_string(), v);
+    json::Value::Object(m)
+}
+
+impl Drop for Ovn {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// `ovn` run as a cluster of processes on this host, talking over loopback on the ports from
+/// `port`, as `cluster.sh` runs it. Process 0, the front end, is `ovn`; the others are killed
+/// when the cluster is dropped.
+pub struct Cluster {
+    pub ovn: Ovn,
+    others: Vec<Child>,
+    hosts: PathBuf
+}
+
+impl Cluster {
+    pub fn spawn(processes: usize, workers: usize, port: u16, args: &[&str]) -> Cluster {
+        let hosts = std::env::temp_dir().join(format!("ovn-cluster-{}.hosts", port));
+        let mut f = File::create(&hosts).unwrap();
+        for p in 0..processes {
+            writeln!(f, "127.0.0.1:{}", port as usize + p).unwrap();
+        };
+        let cluster_args = |p: usize| -> Vec<String> {
+            let mut a: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            a.extend(vec!["-w".to_string(), workers.to_string(), "-n".to_string(), processes.to_string(),
+                          "-p".to_string(), p.to_string(), "-h".to_string(), hosts.to_str().unwrap().to_string()]);
+            a
+        };
+        let others = (1..processes).map(|p| {
+            Command::new(env!("CARGO_BIN_EXE_ovn")).args(cluster_args(p))
+                .stdin(Stdio::null()).stdout(Stdio::null()).spawn()
+                .unwrap_or_else(|e| panic!("{}: {}", env!("CARGO_BIN_EXE_ovn"), e))
+        }).collect();
+        let front = cluster_args(0);
+        let ovn = Ovn::spawn(&front.iter().map(|a| a.as_str()).collect::<Vec<_>>());
+        Cluster { ovn: ovn, others: others, hosts: hosts }
+    }
+}
+
+impl Drop for Cluster {
+    fn drop(&mut self) {
+        for c in &mut self.others {
+            let _ = c.kill();
+            let _ = c.wait();
+        };
+        let _ = fs::remove_file(&self.hosts);
+    }
+}
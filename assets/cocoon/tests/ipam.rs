@@ -0,0 +1,194 @@
+//! Dynamic address allocation: `ipam.rs`, the `LPortDyn*` rules of `ovn.dl` and what they feed.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use std::collections::BTreeMap;
+use std::net::Ipv4Addr;
+
+use serde_json as json;
+
+use common::Ovn;
+
+const TENANT: u32 = 1;
+const LSWITCH: u64 = 1;
+
+/// 10.0.0.n
+fn ip(n: u32) -> u32 {
+    0x0a00_0000 | n
+}
+
+/// A switch on 10.0.0.0/29, whose hosts are 10.0.0.1 to 10.0.0.6.
+fn ovn() -> Ovn {
+    let mut ovn = Ovn::spawn(&[]);
+    ovn.add("LogicalSwitch", row![TENANT, LSWITCH, "LSwitchRegular", "ls1", "10.0.0.0/29"]).unwrap();
+    ovn
+}
+
+fn port(ovn: &mut Ovn, lport: u64) {
+    ovn.add("LogicalSwitchPort", row![TENANT, lport, LSWITCH, "LPortVM", format!("lsp{}", lport), true,
+                                      "NoDHCP4Options", "NoDHCP6Options", false, 0]).unwrap();
+}
+
+/// A port asking for an address, with `lport` as the id of its `LogicalSwitchPortDynAddr`.
+fn dyn_port(ovn: &mut Ovn, lport: u64) {
+    port(ovn, lport);
+    ovn.add("LogicalSwitchPortDynAddr", row![TENANT, lport, lport, 0x0200_0000_0000 | lport, "NoIPAddr"]).unwrap();
+}
+
+fn del_dyn_port(ovn: &mut Ovn, lport: u64) {
+    ovn.del("LogicalSwitchPortDynAddr", row![TENANT, lport, lport, 0x0200_0000_0000 | lport, "NoIPAddr"]).unwrap();
+}
+
+/// The allocated address of each port.
+fn allocs(ovn: &mut Ovn) -> BTreeMap<u64, u32> {
+    ovn.rows("LPortDynIPAlloc").iter().map(|r| {
+        assert_eq!((r[0].as_u64(), r[1].as_u64()), (Some(TENANT as u64), Some(LSWITCH)), "{}", r);
+        (r[2].as_u64().unwrap(), r[3].as_u64().unwrap() as u32)
+    }).collect()
+}
+
+fn exhausted(ovn: &mut Ovn) -> bool {
+    !ovn.rows("LSwitchIPExhausted").is_empty()
+}
+
+#[test]
+fn allocates_distinct_hosts_of_the_subnet() {
+    let mut ovn = ovn();
+    for p in 1..4 {
+        dyn_port(&mut ovn, p);
+    };
+    let a = allocs(&mut ovn);
+    assert_eq!(a.keys().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+    let mut ips: Vec<u32> = a.values().cloned().collect();
+    ips.sort();
+    ips.dedup();
+    assert_eq!(ips.len(), 3);
+    assert!(ips.iter().all(|&i| i >= ip(1) && i <= ip(6)), "{:?}", ips);
+    assert!(!exhausted(&mut ovn));
+}
+
+#[test]
+fn allocations_are_stable() {
+    let mut ovn = ovn();
+    dyn_port(&mut ovn, 1);
+    dyn_port(&mut ovn, 2);
+    let before = allocs(&mut ovn);
+    dyn_port(&mut ovn, 3);
+    port(&mut ovn, 4);
+    ovn.add("LogicalSwitchPortIP", row![TENANT, 4, 0x0200_0000_0004u64, "10.0.0.5"]).unwrap();
+    del_dyn_port(&mut ovn, 3);
+    let after = allocs(&mut ovn);
+    assert_eq!(after.get(&1), before.get(&1));
+    assert_eq!(after.get(&2), before.get(&2));
+}
+
+#[test]
+fn releases_addresses_of_removed_ports() {
+    let mut ovn = ovn();
+    dyn_port(&mut ovn, 1);
+    dyn_port(&mut ovn, 2);
+    let released = allocs(&mut ovn)[&1];
+    del_dyn_port(&mut ovn, 1);
+    assert_eq!(allocs(&mut ovn).keys().cloned().collect::<Vec<_>>(), vec![2]);
+    let inuse = ovn.rows("LSwitchIPInUse");
+    assert!(!inuse.contains(&row![TENANT, LSWITCH, released]), "{:?}", inuse);
+}
+
+#[test]
+fn exhausts_the_subnet_and_recovers() {
+    let mut ovn = ovn();
+    for p in 1..8 {
+        dyn_port(&mut ovn, p);
+    };
+    let a = allocs(&mut ovn);
+    assert_eq!(a.len(), 6);
+    assert!(!a.contains_key(&7));
+    assert_eq!(ovn.rows("LSwitchIPExhausted"), vec![row![TENANT, LSWITCH]]);
+
+    // requests that do not free anything leave the port pending
+    port(&mut ovn, 8);
+    assert!(exhausted(&mut ovn));
+
+    let freed = a[&3];
+    del_dyn_port(&mut ovn, 3);
+    let a = allocs(&mut ovn);
+    assert_eq!(a.get(&7), Some(&freed));
+    assert!(!exhausted(&mut ovn));
+}
+
+#[test]
+fn reassigns_addresses_taken_statically() {
+    let mut ovn = ovn();
+    dyn_port(&mut ovn, 1);
+    let taken = allocs(&mut ovn)[&1];
+    port(&mut ovn, 2);
+    ovn.add("LogicalSwitchPortIP", row![TENANT, 2, 0x0200_0000_0002u64, Ipv4Addr::from(taken).to_string()]).unwrap();
+    let a = allocs(&mut ovn);
+    assert!(a.contains_key(&1));
+    assert!(a[&1] != taken, "port 1 kept {}", taken);
+    let dynip: Vec<_> = ovn.rows("LPortDynIP").iter().map(|r| r[4].as_u64().unwrap() as u32).collect();
+    assert_eq!(dynip, vec![a[&1]]);
+}
+
+#[test]
+fn allocated_macs_reach_port_security() {
+    let mut ovn = ovn();
+    port(&mut ovn, 1);
+    let macs = ovn.rows("LPortDynMACAlloc");
+    assert_eq!(macs.len(), 1);
+    let mac = macs[0][2].clone();
+    ovn.add("PortSecurityMAC", row![TENANT, 1, 0x0200_0000_0001u64]).unwrap();
+    assert!(ovn.rows("PortSecurityAllowedMAC").contains(&row![TENANT, 1, mac.clone()]));
+    let matches = ovn.rows("PortSecurityIP4Match");
+    assert!(matches.iter().any(|r| r[2] == mac), "{:?}", matches);
+}
+
+#[test]
+fn ports_with_several_static_macs_get_no_dynamic_one() {
+    let mut ovn = ovn();
+    port(&mut ovn, 1);
+    ovn.add("LogicalSwitchPortMAC", row![TENANT, 1, 0x0200_0000_0001u64]).unwrap();
+    ovn.add("LogicalSwitchPortMAC", row![TENANT, 1, 0x0200_0000_0002u64]).unwrap();
+    for &rel in &["LPortDynMAC", "LPortDynMACPending", "LPortDynMACAlloc"] {
+        assert_eq!(ovn.rows(rel), Vec::<json::Value>::new(), "{}", rel);
+    };
+    assert_eq!(ovn.rows("LPortMAC"), vec![row![TENANT, 1, 0x0200_0000_0001u64], row![TENANT, 1, 0x0200_0000_0002u64]]);
+}
+
+/// Each `LogicalSwitchPortDynAddr` of a port gives it an `LPortDynIP` row, all with the one
+/// address allocated to the port.
+#[test]
+fn ports_with_several_requests_get_one_address() {
+    let mut ovn = ovn();
+    dyn_port(&mut ovn, 1);
+    ovn.add("LogicalSwitchPortDynAddr", row![TENANT, 2, 1, 0x0200_0000_0100u64, "NoIPAddr"]).unwrap();
+    let a = allocs(&mut ovn);
+    assert_eq!(a.keys().cloned().collect::<Vec<_>>(), vec![1]);
+    assert_eq!(ovn.rows("LPortDynIP"), vec![row![TENANT, LSWITCH, 1, 0x0200_0000_0001u64, a[&1]],
+                                            row![TENANT, LSWITCH, 1, 0x0200_0000_0100u64, a[&1]]]);
+    for &rel in &["LPortDynIPPending", "LPortDynIPStale", "LSwitchIPExhausted"] {
+        assert_eq!(ovn.rows(rel), Vec::<json::Value>::new(), "{}", rel);
+    };
+    assert_eq!(allocs(&mut ovn), a);
+}
+
+#[test]
+fn only_the_allocator_writes_allocations() {
+    let mut ovn = ovn();
+    dyn_port(&mut ovn, 1);
+    let a = allocs(&mut ovn);
+    for &(rel, ref row) in &[("LPortDynIPAlloc", row![TENANT, LSWITCH, 2, ip(6)]),
+                         ("LPortDynIPAlloc", row![TENANT, LSWITCH, 1, a[&1]]),
+                         ("LPortDynMACAlloc", row![TENANT, 2, 0x0a00_0000_0100u64])] {
+        assert!(ovn.add(rel, row.clone()).unwrap_err().contains("--allow-derived-writes"), "{} {}", rel, row);
+        assert!(ovn.del(rel, row.clone()).unwrap_err().contains("--allow-derived-writes"), "{} {}", rel, row);
+    };
+    assert_eq!(allocs(&mut ovn), a);
+
+    let mut ovn = Ovn::spawn(&["--allow-derived-writes"]);
+    ovn.add("LPortDynMACAlloc", row![TENANT, 2, 0x0a00_0000_0100u64]).unwrap();
+    assert_eq!(ovn.rows("LPortDynMACAlloc"), vec![row![TENANT, 2, 0x0a00_0000_0100u64]]);
+}
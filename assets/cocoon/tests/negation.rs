@@ -0,0 +1,75 @@
+//! `not` literals whose relation holds several facts for one key.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::Ovn;
+
+fn chassis(ovn: &mut Ovn, c: u32) {
+    ovn.add("Chassis", row![c, false, format!("hv{}", c), format!("192.168.0.{}", c)]).unwrap();
+    ovn.add("TunnelPort", row![c, 1, c, 0xc0a8_0000u32 | c]).unwrap();
+}
+
+/// A VM port with two static MACs, in tenant 1.
+fn port_with_two_macs(ovn: &mut Ovn, lport: u64) {
+    ovn.add("LogicalSwitch", row![1, 1, "LSwitchRegular", "ls1", "NoSubnet"]).unwrap();
+    ovn.add("LogicalSwitchPort", row![1, lport, 1, "LPortVM", format!("lsp{}", lport), true,
+                                      "NoDHCP4Options", "NoDHCP6Options", false, 0]).unwrap();
+    ovn.add("LogicalSwitchPortMAC", row![1, lport, 0x0200_0000_0001u64]).unwrap();
+    ovn.add("LogicalSwitchPortMAC", row![1, lport, 0x0200_0000_0002u64]).unwrap();
+}
+
+fn no_encap(ovn: &mut Ovn) -> Vec<json::Value> {
+    ovn.rows("TunnelNoEncap")
+}
+
+/// `TunnelNoEncap` negates `TunnelEncap(from, to, _, _)`, which holds one fact per shared
+/// encapsulation.
+#[test]
+fn negates_keys_matching_several_facts_once() {
+    let mut ovn = Ovn::spawn(&[]);
+    chassis(&mut ovn, 1);
+    chassis(&mut ovn, 2);
+    for c in 1..3 {
+        for &e in &["EncapGeneve", "EncapVXLAN", "EncapSTT"] {
+            ovn.add("ChassisEncap", row![c, e, 1]).unwrap();
+        }
+    };
+    assert_eq!(no_encap(&mut ovn), Vec::<json::Value>::new());
+    ovn.del("ChassisEncap", row![2, "EncapSTT", 1]).unwrap();
+    assert_eq!(no_encap(&mut ovn), Vec::<json::Value>::new());
+    ovn.del("ChassisEncap", row![2, "EncapVXLAN", 1]).unwrap();
+    assert_eq!(no_encap(&mut ovn), Vec::<json::Value>::new());
+    ovn.del("ChassisEncap", row![1, "EncapGeneve", 1]).unwrap();
+    assert_eq!(no_encap(&mut ovn), vec![row![1, 2], row![2, 1]]);
+    ovn.add("ChassisEncap", row![2, "EncapVXLAN", 1]).unwrap();
+    assert_eq!(no_encap(&mut ovn), Vec::<json::Value>::new());
+}
+
+/// Rules whose `not` projects a relation onto some of its columns, interpreted and compiled.
+#[test]
+fn interpreted_rules_agree_with_compiled_ones() {
+    let mut compiled = Ovn::spawn(&[]);
+    let mut interpreted = Ovn::spawn(&["--rules", concat!(env!("CARGO_MANIFEST_DIR"), "/ovn.dl")]);
+    for ovn in [&mut compiled, &mut interpreted].iter_mut() {
+        chassis(ovn, 1);
+        chassis(ovn, 2);
+        chassis(ovn, 3);
+        for c in 1..3 {
+            for &e in &["EncapGeneve", "EncapVXLAN"] {
+                ovn.add("ChassisEncap", row![c, e, 1]).unwrap();
+            }
+        };
+        ovn.add("ChassisEncap", row![3, "EncapSTT", 1]).unwrap();
+        port_with_two_macs(ovn, 1);
+    };
+    for &rel in &["TunnelEncapWorse", "TunnelFromTo", "TunnelNoEncap", "LPortDynMAC", "LPortDynMACPending"] {
+        assert_eq!(interpreted.rows(rel), compiled.rows(rel), "{}", rel);
+    };
+    assert_eq!(compiled.rows("TunnelNoEncap").len(), 4);
+    assert_eq!(compiled.rows("LPortDynMACPending"), Vec::<json::Value>::new());
+}
@@ -0,0 +1,508 @@
+//! Differential tests of the rules against a reference written out by hand.
+//!
+//! `reference` computes the derived relations in `DERIVED` from the rows of the input
+//! relations, with one block of nested loops per rule of `ovn.dl`, reading the JSON rows
+//! directly. It shares nothing with the rules compiler or the interpreter, so a rule of
+//! `ovn.dl` that says the wrong thing shows up as a difference, instead of being evaluated
+//! the same wrong way twice.
+//!
+//! The tests run the `ovn` binary, send it random sequences of `add`, `del`, `start`, `commit`
+//! and `rollback` requests, and check after every step that `enm` of each input relation
+//! matches the writes that went through, and that `enm` of each relation in `DERIVED` is what
+//! `reference` computes from the inputs.
+
+extern crate rand;
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::{Rng, SeedableRng, StdRng};
+use serde_json as json;
+
+use common::{obj, Ovn};
+
+/// Relations the address allocator writes to on its own.
+const ALLOCATED: &[&str] = &["LPortDynIPAlloc", "LPortDynMACAlloc"];
+
+/// The relations `reference` computes.
+const DERIVED: &[&str] = &["TrunkPort", "MACStatic", "LPortDynMAC", "LPortMAC", "LPortAtChassis", "LSwitchAtChassis",
+                           "LPortMACChassis", "MACChassis", "LPortUnknownMACChassis", "UnknownMACChassis",
+                           "LRouterNetwork", "Route", "NATChassis", "NATChassis6", "ChassisEncapSupported", "TunnelEncap",
+                           "TunnelFromToEncap", "TunnelFromTo", "TunnelNoEncap"];
+
+/// The variants of `encap_type_t`, in order.
+const ENCAPS: &[&str] = &["EncapGeneve", "EncapSTT", "EncapVXLAN"];
+
+/// A few values of each column type, in the structured form `enm` returns them in, and few
+/// enough that rows often join. The addresses make static routes with next hops on and off
+/// the port's network.
+fn samples(ty: &str) -> Vec<&'static str> {
+    match ty {
+        "bool"                   => vec![r#"false"#, r#"true"#],
+        "u8"                     => vec![r#"6"#, r#"17"#],
+        "u16"                    => vec![r#"1"#, r#"2"#],
+        "u32" | "u64"            => vec![r#"1"#, r#"2"#, r#"3"#],
+        "String"                 => vec![r#""a""#, r#""b""#],
+        "U128"                   => vec![r#""1""#, r#""2""#],
+        "__lambda"               => vec![r#"{"__Lambda": {"__lambda_string": "true"}}"#],
+        "acl_action_t"           => vec![r#""ACLAllow""#, r#""ACLDrop""#],
+        "acl_dir_t"              => vec![r#""ACLTo""#, r#""ACLFrom""#],
+        "dhcp4_options_t"        => vec![r#"{"DHCP4Options": {"cidr": {"IP4Subnet": {"addr": 167772160, "mask": 4294967040}},
+                                             "server_id": 167772161, "server_mac": 2199023255553,
+                                             "router": 167772161, "lease_time": 3600}}"#],
+        "dhcp6_options_t"        => vec![r#"{"DHCP6Options": {"cidr": {"IP4Subnet": {"addr": 167772160, "mask": 4294967040}},
+                                             "server_id": {"DUID_LL": {"hw_type": 1, "mac": 1}}}}"#],
+        "encap_type_t"           => vec![r#""EncapGeneve""#, r#""EncapSTT""#, r#""EncapVXLAN""#],
+        "ip4_addr_t"             => vec![r#"167772161"#, r#"167772162"#],
+        "ip4_addr_port_t"        => vec![r#"{"IP4AddrPort": {"addr": 167772161, "prt": 80}}"#],
+        "ip4_subnet_t"           => vec![r#"{"IP4Subnet": {"addr": 167772160, "mask": 4278190080}}"#,
+                                         r#"{"IP4Subnet": {"addr": 167772161, "mask": 4294967040}}"#],
+        "ip6_addr_port_t"        => vec![r#"{"IP6AddrPort": {"addr": "336294682933583715844663186250927177729", "prt": 80}}"#],
+        "ip6_subnet_t"           => vec![r#"{"IP6Subnet": {"addr": "336294682933583715844663186250927177729",
+                                                           "mask": "340282366920938463444927863358058659840"}}"#],
+        "ip_addr_t"              => vec![r#"{"IPAddr4": {"addr4": 167772162}}"#,
+                                         r#"{"IPAddr4": {"addr4": 167837697}}"#,
+                                         r#"{"IPAddr6": {"addr6": "336294682933583715844663186250927177730"}}"#,
+                                         r#"{"IPAddr6": {"addr6": "338288524927261089654018896841347694593"}}"#],
+        "ip_subnet_t"            => vec![r#"{"IPSubnet4": {"ip4_subnet": {"IP4Subnet": {"addr": 167772161, "mask": 4294967040}}}}"#,
+                                         r#"{"IPSubnet4": {"ip4_subnet": {"IP4Subnet": {"addr": 167772160, "mask": 4278190080}}}}"#,
+                                         r#"{"IPSubnet6": {"ip6_subnet": {"IP6Subnet": {"addr": "336294682933583715844663186250927177729",
+                                                                                        "mask": "340282366920938463444927863358058659840"}}}}"#],
+        "lport_type_t"           => vec![r#""LPortVM""#,
+                                         r#"{"LPortVIF": {"parent": 1, "tag_request": 0, "tag": 0}}"#,
+                                         r#"{"LPortRouter": {"rport": 1}}"#,
+                                         r#"{"LPortLocalnet": {"localnet": 1}}"#,
+                                         r#"{"LPortL2Gateway": {"pnet": 1, "chassis": 2}}"#],
+        "lrouter_port_type_t"    => vec![r#""LRPRegular""#, r#"{"LRPGateway": {"redirectChassis": 1}}"#],
+        "lrouter_type_t"         => vec![r#""RouterRegular""#],
+        "lswitch_type_t"         => vec![r#""LSwitchRegular""#],
+        "mac_addr_t"             => vec![r#"2199023255553"#, r#"2199023255554"#],
+        "nat_type_t"             => vec![r#""SNAT""#, r#""DNAT""#],
+        "opt_dhcp4_options_id_t" => vec![r#""NoDHCP4Options""#, r#"{"SomeDHCP4Options": {"options": 1}}"#],
+        "opt_dhcp6_options_id_t" => vec![r#""NoDHCP6Options""#, r#"{"SomeDHCP6Options": {"options": 1}}"#],
+        "opt_ip_addr_t"          => vec![r#""NoIPAddr""#, r#"{"SomeIPAddr": {"addr": {"IPAddr4": {"addr4": 167772162}}}}"#],
+        "opt_lport_id_t"         => vec![r#""NoLPortId""#, r#"{"SomeLPortId": {"id": 1}}"#],
+        "opt_mac_addr_t"         => vec![r#""NoMACAddr""#, r#"{"SomeMACAddr": {"addr": 2199023255553}}"#],
+        "opt_peer_t"             => vec![r#""NoPeer""#, r#"{"SomePeer": {"peer": 1}}"#],
+        "opt_subnet_t"           => vec![r#""NoSubnet""#,
+                                         r#"{"SomeSubnet": {"subnet": {"IPSubnet4": {"ip4_subnet": {"IP4Subnet": {"addr": 167772160, "mask": 4294967288}}}}}}"#],
+        "port_sec_type_t"        => vec![r#""PortSecNone""#, r#""PortSecMAC""#],
+        _                        => panic!("no samples of type {}", ty)
+    }
+}
+
+/// The rows of the input relations, by name.
+struct Db(BTreeMap<String, Vec<json::Value>>);
+
+impl Db {
+    fn rows(&self, rel: &str) -> &[json::Value] {
+        self.0.get(rel).map_or(&[][..], |rows| &rows[..])
+    }
+}
+
+/// The fields of `v` if it is variant `name` of an enum, or `v` itself for a variant without
+/// fields.
+fn variant<'a>(v: &'a json::Value, name: &str) -> Option<&'a json::Value> {
+    match *v {
+        json::Value::String(ref s) if s == name    => Some(v),
+        json::Value::Object(ref m) if m.len() == 1 => m.get(name),
+        _                                          => None
+    }
+}
+
+/// `{"f1": v1, "f2": v2, ...}`, the fields of a struct variant.
+fn fields(fs: &[(&str, json::Value)]) -> json::Value {
+    json::Value::Object(fs.iter().map(|&(f, ref v)| (f.to_string(), v.clone())).collect())
+}
+
+fn u128_of(v: &json::Value) -> u128 {
+    v.as_str().and_then(|s| s.parse().ok()).unwrap_or_else(|| panic!("not a U128: {}", v))
+}
+
+/// The address and mask of an `ip_subnet_t`, as 128-bit numbers, and whether it is IPv6.
+fn subnet(net: &json::Value) -> (u128, u128, bool) {
+    if let Some(s) = variant(net, "IPSubnet4") {
+        let s = &s["ip4_subnet"]["IP4Subnet"];
+        (s["addr"].as_u64().unwrap() as u128, s["mask"].as_u64().unwrap() as u128, false)
+    } else {
+        let s = &variant(net, "IPSubnet6").unwrap()["ip6_subnet"]["IP6Subnet"];
+        (u128_of(&s["addr"]), u128_of(&s["mask"]), true)
+    }
+}
+
+/// The `ip_addr_t` `addr`, of the given family.
+fn ip_addr(addr: u128, v6: bool) -> json::Value {
+    if v6 {
+        obj("IPAddr6", obj("addr6", json::Value::from(addr.to_string())))
+    } else {
+        obj("IPAddr4", obj("addr4", json::Value::from(addr as u64)))
+    }
+}
+
+/// The `ip_subnet_t` of `addr` and `mask`, of the given family.
+fn ip_subnet(addr: u128, mask: u128, v6: bool) -> json::Value {
+    if v6 {
+        let (a, m) = (json::Value::from(addr.to_string()), json::Value::from(mask.to_string()));
+        obj("IPSubnet6", obj("ip6_subnet", obj("IP6Subnet", fields(&[("addr", a), ("mask", m)]))))
+    } else {
+        let (a, m) = (json::Value::from(addr as u64), json::Value::from(mask as u64));
+        obj("IPSubnet4", obj("ip4_subnet", obj("IP4Subnet", fields(&[("addr", a), ("mask", m)]))))
+    }
+}
+
+/// The address and family of an `ip_addr_t`.
+fn address(ip: &json::Value) -> (u128, bool) {
+    match variant(ip, "IPAddr4") {
+        Some(a) => (a["addr4"].as_u64().unwrap() as u128, false),
+        None    => (u128_of(&variant(ip, "IPAddr6").unwrap()["addr6"]), true)
+    }
+}
+
+fn dst_chassis(chassis: &json::Value) -> json::Value {
+    obj("DSTChassis", obj("chassis", chassis.clone()))
+}
+
+/// The rows of the relations in `DERIVED`, computed from `db` by following each rule of
+/// `ovn.dl` literally: namespaced atoms join on the tenant in column 0, and rows are pushed
+/// once per way the body holds, so duplicates are left to the sets of the result.
+fn reference(db: &Db) -> BTreeMap<&'static str, Vec<json::Value>> {
+    let mut out = BTreeMap::new();
+    let lsps = db.rows("LogicalSwitchPort");
+    let lrps = db.rows("LogicalRouterPort");
+    let live: Vec<json::Value> = db.rows("Chassis").iter().filter(|c| c[1] == false).map(|c| c[0].clone()).collect();
+    // the chassis of `lport`'s `LPortBinding`
+    let bound = |t: &json::Value, lport: &json::Value| -> Vec<json::Value> {
+        let mut cs = Vec::new();
+        for b in db.rows("LPortBinding").iter().filter(|b| b[0] == *t && b[1] == *lport) {
+            for v in db.rows("VSwitchPort").iter().filter(|v| v[0] == b[2]) {
+                cs.push(v[2].clone());
+            };
+        };
+        cs
+    };
+    let is_vm = |t: &json::Value, lport: &json::Value| {
+        lsps.iter().any(|p| p[0] == *t && p[1] == *lport && variant(&p[3], "LPortVM").is_some())
+    };
+
+    let mut trunk = Vec::new();
+    for p in lsps {
+        for q in lsps.iter().filter(|q| q[0] == p[0]) {
+            match variant(&q[3], "LPortVIF") {
+                Some(vif) if vif["parent"] == p[1] => trunk.push(row![p[0].clone(), p[1].clone()]),
+                _                                  => ()
+            };
+        };
+    };
+
+    let mut mac_static = Vec::new();
+    for m in db.rows("LogicalSwitchPortMAC") { mac_static.push(row![m[0].clone(), m[2].clone()]) };
+    for m in db.rows("LogicalSwitchPortIP") { mac_static.push(row![m[0].clone(), m[2].clone()]) };
+    for m in db.rows("LogicalSwitchPortDynAddr") { mac_static.push(row![m[0].clone(), m[3].clone()]) };
+    for m in lrps { mac_static.push(row![m[0].clone(), m[5].clone()]) };
+
+    let mut dyn_mac = Vec::new();
+    for a in db.rows("LPortDynMACAlloc") {
+        for p in lsps.iter().filter(|p| p[0] == a[0] && p[1] == a[1]) {
+            if (variant(&p[3], "LPortVM").is_some() || variant(&p[3], "LPortVIF").is_some())
+                && !db.rows("LogicalSwitchPortMAC").iter().any(|m| m[0] == a[0] && m[1] == a[1])
+                && !mac_static.contains(&row![a[0].clone(), a[2].clone()]) {
+                dyn_mac.push(a.clone());
+            };
+        };
+    };
+    let mut lport_mac: Vec<json::Value> = db.rows("LogicalSwitchPortMAC").to_vec();
+    lport_mac.extend(dyn_mac.iter().cloned());
+
+    let mut at = Vec::new();
+    for p in lsps.iter().filter(|p| p[5] == true) {
+        let (t, lport, lswitch) = (&p[0], &p[1], &p[2]);
+        if variant(&p[3], "LPortVM").is_some() && !trunk.contains(&row![t.clone(), lport.clone()]) {
+            for c in bound(t, lport) { at.push(row![t.clone(), lport.clone(), lswitch.clone(), c, false]) };
+        };
+        if let Some(vif) = variant(&p[3], "LPortVIF") {
+            if is_vm(t, &vif["parent"]) {
+                for c in bound(t, &vif["parent"]) { at.push(row![t.clone(), lport.clone(), lswitch.clone(), c, false]) };
+            };
+        };
+        if variant(&p[3], "LPortLocalnet").is_some() || variant(&p[3], "LPortRouter").is_some() {
+            for c in &live { at.push(row![t.clone(), lport.clone(), lswitch.clone(), c.clone(), true]) };
+        };
+        if let Some(gw) = variant(&p[3], "LPortL2Gateway") {
+            at.push(row![t.clone(), lport.clone(), lswitch.clone(), gw["chassis"].clone(), false]);
+        };
+    };
+
+    let mut switch_at = Vec::new();
+    for s in &live {
+        for a in &at {
+            if a[3] == *s { switch_at.push(row![a[0].clone(), s.clone(), a[2].clone(), "DSTLocal"]) };
+            if a[4] == false && a[3] != *s { switch_at.push(row![a[0].clone(), s.clone(), a[2].clone(), dst_chassis(&a[3])]) };
+        };
+    };
+
+    let mut mac_at = Vec::new();
+    for p in lsps.iter().filter(|p| p[5] == true) {
+        let (t, lport, lswitch) = (&p[0], &p[1], &p[2]);
+        let mut places = Vec::new();
+        if let Some(vif) = variant(&p[3], "LPortVIF") {
+            if is_vm(t, &vif["parent"]) {
+                places.extend(bound(t, &vif["parent"]).into_iter().map(|c| (c, false)));
+            };
+        };
+        if variant(&p[3], "LPortVM").is_some() {
+            places.extend(bound(t, lport).into_iter().map(|c| (c, false)));
+        };
+        if variant(&p[3], "LPortLocalnet").is_some() || variant(&p[3], "LPortRouter").is_some() {
+            places.extend(live.iter().map(|c| (c.clone(), true)));
+        };
+        if let Some(gw) = variant(&p[3], "LPortL2Gateway") {
+            places.push((gw["chassis"].clone(), false));
+        };
+        for m in lport_mac.iter().filter(|m| m[0] == *t && m[1] == *lport) {
+            for &(ref c, fl) in &places {
+                mac_at.push(row![t.clone(), lswitch.clone(), lport.clone(), m[2].clone(), c.clone(), fl]);
+            };
+        };
+    };
+
+    let mut mac_chassis = Vec::new();
+    for m in &mac_at {
+        let dst = if m[5] == true { json::Value::from("DSTLocal") } else { dst_chassis(&m[4]) };
+        mac_chassis.push(row![m[0].clone(), m[1].clone(), m[3].clone(), dst]);
+    };
+
+    let mut unknown_at = Vec::new();
+    for p in lsps.iter().filter(|p| p[5] == true && p[8] == true) {
+        let (t, lport, lswitch) = (&p[0], &p[1], &p[2]);
+        if let Some(vif) = variant(&p[3], "LPortVIF") {
+            if is_vm(t, &vif["parent"]) {
+                for c in bound(t, lport) { unknown_at.push(row![t.clone(), lswitch.clone(), lport.clone(), c, false]) };
+            };
+        };
+        if variant(&p[3], "LPortVM").is_some() && !trunk.contains(&row![t.clone(), lport.clone()]) {
+            for c in bound(t, lport) { unknown_at.push(row![t.clone(), lswitch.clone(), lport.clone(), c, false]) };
+        };
+        if variant(&p[3], "LPortLocalnet").is_some() || variant(&p[3], "LPortRouter").is_some() {
+            for c in &live { unknown_at.push(row![t.clone(), lswitch.clone(), lport.clone(), c.clone(), true]) };
+        };
+        if let Some(gw) = variant(&p[3], "LPortL2Gateway") {
+            unknown_at.push(row![t.clone(), lswitch.clone(), lport.clone(), gw["chassis"].clone(), false]);
+        };
+    };
+
+    let mut unknown_chassis = Vec::new();
+    for s in &live {
+        for u in &unknown_at {
+            if u[3] == *s { unknown_chassis.push(row![u[0].clone(), s.clone(), u[1].clone(), "DSTLocal"]) };
+            if u[4] == false && u[3] != *s { unknown_chassis.push(row![u[0].clone(), s.clone(), u[1].clone(), dst_chassis(&u[3])]) };
+        };
+    };
+
+    let nets = db.rows("LRouterPortNetwork");
+    let mut router_net = Vec::new();
+    for rp in lrps {
+        for n in nets.iter().filter(|n| n[0] == rp[0] && n[1] == rp[1]) {
+            router_net.push(row![rp[0].clone(), rp[3].clone(), n[2].clone()]);
+        };
+    };
+
+    let mut route = Vec::new();
+    for s in db.rows("LogicalRouterStaticRoute") {
+        let (nexthop, v6) = address(&s[3]);
+        for rp in lrps.iter().filter(|rp| rp[0] == s[0] && rp[1] == s[4]) {
+            for n in nets.iter().filter(|n| n[0] == s[0] && n[1] == s[4]) {
+                let (addr, mask, net_v6) = subnet(&n[2]);
+                if net_v6 == v6 && nexthop & mask == addr & mask {
+                    route.push(row![s[0].clone(), s[1].clone(), s[2].clone(), obj("SomeIPAddr", obj("addr", s[3].clone())),
+                                    s[4].clone(), rp[5].clone(), ip_addr(addr, v6)]);
+                };
+            };
+        };
+    };
+    for n in nets {
+        let (addr, mask, v6) = subnet(&n[2]);
+        for rp in lrps.iter().filter(|rp| rp[0] == n[0] && rp[1] == n[1] && rp[6] == true) {
+            route.push(row![n[0].clone(), rp[3].clone(), ip_subnet(addr & mask, mask, v6), "NoIPAddr",
+                            n[1].clone(), rp[5].clone(), ip_addr(addr, v6)]);
+        };
+    };
+
+    let mut nat_chassis = BTreeMap::new();
+    for &(nat, chassis) in &[("NAT", "NATChassis"), ("NAT6", "NATChassis6")] {
+        let mut rows = Vec::new();
+        for n in db.rows(nat) {
+            let lport = match variant(&n[6], "SomeLPortId") {
+                Some(l) => l["id"].clone(),
+                None    => continue
+            };
+            if variant(&n[4], "NoMACAddr").is_some() {
+                for rp in lrps.iter().filter(|rp| rp[0] == n[0] && rp[3] == n[1] && rp[6] == true) {
+                    if let Some(gw) = variant(&rp[4], "LRPGateway") {
+                        rows.push(row![n[0].clone(), n[1].clone(), n[2].clone(), n[3].clone(), n[4].clone(), n[5].clone(),
+                                       0, gw["redirectChassis"].clone()]);
+                    };
+                };
+            } else {
+                for a in at.iter().filter(|a| a[0] == n[0] && a[1] == lport && a[4] == false) {
+                    rows.push(row![n[0].clone(), n[1].clone(), n[2].clone(), n[3].clone(), n[4].clone(), n[5].clone(),
+                                   lport.clone(), a[3].clone()]);
+                };
+            };
+        };
+        nat_chassis.insert(chassis, rows);
+    };
+
+    let mut encaps: Vec<json::Value> = db.rows("ChassisEncap").to_vec();
+    for c in db.rows("Chassis").iter().map(|c| &c[0]).chain(db.rows("TunnelPort").iter().map(|tp| &tp[2])) {
+        if !db.rows("ChassisEncap").iter().any(|e| e[0] == *c) {
+            encaps.push(row![c.clone(), "EncapGeneve", 0]);
+        };
+    };
+    let mut tunnel_encap = Vec::new();
+    for f in &encaps {
+        for t in encaps.iter().filter(|t| t[1] == f[1] && t[0] != f[0]) {
+            let cost = f[2].as_u64().unwrap() + t[2].as_u64().unwrap();
+            tunnel_encap.push(row![f[0].clone(), t[0].clone(), f[1].clone(), cost]);
+        };
+    };
+
+    // an encapsulation of a pair is worse if another of the pair costs less, or the same and
+    // comes first in `encap_type_t`
+    let order = |e: &json::Value| ENCAPS.iter().position(|n| e == n).unwrap();
+    let worse = |e: &json::Value| tunnel_encap.iter().filter(|w| w[0] == e[0] && w[1] == e[1] && w[2] == e[2]).any(|w| {
+        tunnel_encap.iter().filter(|o| o[0] == w[0] && o[1] == w[1])
+            .any(|o| o[3].as_u64() < w[3].as_u64() || (o[3] == w[3] && order(&o[2]) < order(&w[2])))
+    });
+    let mut tunnel_with_encap = Vec::new();
+    let mut tunnel = Vec::new();
+    let mut no_encap = Vec::new();
+    for tp in db.rows("TunnelPort") {
+        for from in db.rows("Chassis").iter().map(|c| &c[0]) {
+            for e in tunnel_encap.iter().filter(|e| e[0] == *from && e[1] == tp[2]) {
+                if !worse(e) {
+                    tunnel_with_encap.push(row![from.clone(), tp[2].clone(), tp[3].clone(), e[2].clone()]);
+                    tunnel.push(row![from.clone(), tp[2].clone(), tp[3].clone()]);
+                };
+            };
+            if *from != tp[2] && !tunnel_encap.iter().any(|e| e[0] == *from && e[1] == tp[2]) {
+                no_encap.push(row![from.clone(), tp[2].clone()]);
+            };
+        };
+    };
+
+    out.insert("TrunkPort", trunk);
+    out.insert("MACStatic", mac_static);
+    out.insert("LPortDynMAC", dyn_mac);
+    out.insert("LPortMAC", lport_mac);
+    out.insert("LPortAtChassis", at);
+    out.insert("LSwitchAtChassis", switch_at);
+    out.insert("LPortMACChassis", mac_at);
+    out.insert("MACChassis", mac_chassis);
+    out.insert("LPortUnknownMACChassis", unknown_at);
+    out.insert("UnknownMACChassis", unknown_chassis);
+    out.insert("LRouterNetwork", router_net);
+    out.insert("Route", route);
+    out.extend(nat_chassis);
+    out.insert("ChassisEncapSupported", encaps);
+    out.insert("TunnelEncap", tunnel_encap);
+    out.insert("TunnelFromToEncap", tunnel_with_encap);
+    out.insert("TunnelFromTo", tunnel);
+    out.insert("TunnelNoEncap", no_encap);
+    out
+}
+
+/// Rows as a set, each in its canonical text.
+fn set(rows: &[json::Value]) -> BTreeSet<String> {
+    rows.iter().map(|r| r.to_string()).collect()
+}
+
+fn send(ovn: &mut Ovn, log: &mut Vec<String>, req: json::Value) -> Result<json::Value, String> {
+    log.push(req.to_string());
+    ovn.request(req)
+}
+
+/// Sends `steps` random writes, in and out of transactions, to an `ovn` run with `args`, and
+/// checks the inputs against the writes and the relations in `DERIVED` against `reference`
+/// after each.
+fn differential(args: &[&str], seed: usize, steps: usize) {
+    let mut ovn = Ovn::spawn(args);
+    let schema = ovn.request(json::Value::from("schema")).unwrap();
+    let inputs: Vec<(String, Vec<String>)> = schema.as_array().unwrap().iter()
+        .filter(|r| r["kind"] == "input")
+        .map(|r| (r["name"].as_str().unwrap().to_string(),
+                  r["columns"].as_array().unwrap().iter().map(|c| c["type"].as_str().unwrap().to_string()).collect()))
+        .collect();
+    let writable: Vec<&(String, Vec<String>)> = inputs.iter().filter(|r| !ALLOCATED.contains(&&r.0[..])).collect();
+    let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+    let mut log = Vec::new();
+    let mut model: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut committed: Option<BTreeMap<String, BTreeSet<String>>> = None;
+
+    for step in 0..steps {
+        match rng.gen_range(0, 10) {
+            0 if committed.is_none() => {
+                send(&mut ovn, &mut log, json::Value::from("start")).unwrap();
+                committed = Some(model.clone());
+            },
+            1 if committed.is_some() => {
+                send(&mut ovn, &mut log, json::Value::from("commit")).unwrap();
+                committed = None;
+            },
+            2 if committed.is_some() => {
+                send(&mut ovn, &mut log, json::Value::from("rollback")).unwrap();
+                model = committed.take().unwrap();
+            },
+            3 | 4 => {
+                let rel = &rng.choose(&writable).unwrap().0;
+                let rows: Vec<String> = model.get(rel).map(|s| s.iter().cloned().collect()).unwrap_or_default();
+                if let Some(row) = rng.choose(&rows) {
+                    if send(&mut ovn, &mut log, obj("del", obj(rel, json::from_str(row).unwrap()))).is_ok() {
+                        model.get_mut(rel).unwrap().remove(row);
+                    };
+                };
+            },
+            _ => {
+                let (rel, tys) = { let r = rng.choose(&writable).unwrap(); (&r.0, &r.1) };
+                let row = json::Value::Array(tys.iter().map(|ty| json::from_str(rng.choose(&samples(ty)).unwrap()).unwrap()).collect());
+                if send(&mut ovn, &mut log, obj("add", obj(rel, row.clone()))).is_ok() {
+                    model.entry(rel.clone()).or_default().insert(row.to_string());
+                };
+            }
+        };
+
+        let db = Db(inputs.iter().map(|r| (r.0.clone(), ovn.rows(&r.0))).collect());
+        for rel in writable.iter().map(|r| &r.0) {
+            let expected = model.get(rel).cloned().unwrap_or_default();
+            assert!(set(db.rows(rel)) == expected, "seed {}, step {}: input {} is {:?}, expected {:?}\nrequests:\n{}",
+                    seed, step, rel, set(db.rows(rel)), expected, log.join("\n"));
+        };
+        for (rel, rows) in reference(&db) {
+            let (actual, expected) = (set(&ovn.rows(rel)), set(&rows));
+            assert!(actual == expected, "seed {}, step {}: {} is {:?}, expected {:?}\nrequests:\n{}",
+                    seed, step, rel, actual, expected, log.join("\n"));
+        };
+    };
+}
+
+#[test]
+fn reference_covers_the_derived_relations() {
+    let out = reference(&Db(BTreeMap::new()));
+    assert_eq!(out.keys().cloned().collect::<BTreeSet<_>>(), DERIVED.iter().cloned().collect());
+}
+
+#[test]
+fn compiled_rules() {
+    for seed in 0..4 {
+        differential(&[], seed, 200);
+    };
+}
+
+#[test]
+fn interpreted_rules() {
+    for seed in 0..4 {
+        differential(&["--rules", concat!(env!("CARGO_MANIFEST_DIR"), "/ovn.dl")], seed, 200);
+    };
+}
@@ -0,0 +1,92 @@
+//! A follower of a primary, as `replica.rs` describes, before and after it is promoted.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use serde_json as json;
+
+use common::Ovn;
+
+const TENANT: u32 = 1;
+
+const RELATIONS: &[&str] = &["LogicalSwitch", "LogicalSwitchPort", "LPortDynIPAlloc", "LPortDynMACAlloc",
+                             "LPortDynIP", "LPortMAC", "TunnelFromTo"];
+
+fn port(ovn: &mut Ovn, p: u64) {
+    ovn.add("LogicalSwitchPort", row![TENANT, p, 1, "LPortVM", format!("lsp{}", p), true,
+                                      "NoDHCP4Options", "NoDHCP6Options", false, 0]).unwrap();
+    ovn.add("LogicalSwitchPortDynAddr", row![TENANT, p, p, 0x0200_0000_0000 | p, "NoIPAddr"]).unwrap();
+}
+
+fn chassis(ovn: &mut Ovn, c: u32) {
+    ovn.add("Chassis", row![c, false, format!("hv{}", c), format!("192.168.0.{}", c)]).unwrap();
+    ovn.add("TunnelPort", row![c, 1, c, 0xc0a8_0000u32 | c]).unwrap();
+}
+
+fn state(ovn: &mut Ovn) -> Vec<Vec<json::Value>> {
+    RELATIONS.iter().map(|rel| ovn.rows(rel)).collect()
+}
+
+/// Waits for `follower` to have applied what it was sent, and checks it has what `primary` has.
+fn caught_up(follower: &mut Ovn, primary: &mut Ovn) {
+    let expected = state(primary);
+    for _ in 0..100 {
+        if state(follower) == expected { return };
+        thread::sleep(Duration::from_millis(50));
+    };
+    assert_eq!(state(follower), expected);
+}
+
+fn promotes_a_follower_with_the_state_of_the_primary(args: &[&str], addr: &str) {
+    let mut primary_args = vec!["--replicate", addr];
+    primary_args.extend_from_slice(args);
+    let mut primary = Ovn::spawn(&primary_args);
+    primary.add("LogicalSwitch", row![TENANT, 1, "LSwitchRegular", "ls1", "10.0.0.0/24"]).unwrap();
+    port(&mut primary, 1);
+    chassis(&mut primary, 1);
+
+    let mut follower_args = vec!["--follow", addr];
+    follower_args.extend_from_slice(args);
+    let mut follower = Ovn::spawn(&follower_args);
+    caught_up(&mut follower, &mut primary);
+
+    // changes made outside of transactions, and committed ones; not the rolled back ones
+    port(&mut primary, 2);
+    chassis(&mut primary, 2);
+    primary.send("\"start\"").unwrap();
+    port(&mut primary, 3);
+    primary.del("Chassis", row![1, false, "hv1", "192.168.0.1"]).unwrap();
+    primary.send("\"commit\"").unwrap();
+    primary.send("\"start\"").unwrap();
+    port(&mut primary, 4);
+    primary.send("\"rollback\"").unwrap();
+    caught_up(&mut follower, &mut primary);
+    assert!(follower.add("Chassis", row![3, false, "hv3", "192.168.0.3"]).is_err());
+
+    follower.send("\"promote\"").unwrap();
+    drop(primary);
+    port(&mut follower, 5);
+    let mut alone = Ovn::spawn(args);
+    alone.add("LogicalSwitch", row![TENANT, 1, "LSwitchRegular", "ls1", "10.0.0.0/24"]).unwrap();
+    for p in &[1, 2, 3] {
+        port(&mut alone, *p);
+    };
+    chassis(&mut alone, 2);
+    port(&mut alone, 5);
+    assert_eq!(state(&mut follower), state(&mut alone));
+}
+
+#[test]
+fn promotes_a_follower_with_the_state_of_the_primary_compiled() {
+    promotes_a_follower_with_the_state_of_the_primary(&[], "127.0.0.1:21040");
+}
+
+#[test]
+fn promotes_a_follower_with_the_state_of_the_primary_interpreted() {
+    promotes_a_follower_with_the_state_of_the_primary(&["--rules", concat!(env!("CARGO_MANIFEST_DIR"), "/ovn.dl")], "127.0.0.1:21041");
+}
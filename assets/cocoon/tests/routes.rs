@@ -0,0 +1,107 @@
+//! Routing tables: `Route` in `ovn.dl`, from static routes and the networks of router ports.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::{obj, Ovn};
+
+const TENANT: u32 = 1;
+const LROUTER: u64 = 1;
+const MAC: u64 = 0x0200_0000_0001;
+
+/// 10.0.0.n
+fn ip4(n: u32) -> u32 {
+    0x0a00_0000 | n
+}
+
+/// 2001:db8::n
+fn ip6(n: u32) -> String {
+    (0x2001_0db8u128 << 96 | n as u128).to_string()
+}
+
+fn addr4(a: u32) -> json::Value {
+    obj("IPAddr4", obj("addr4", json::Value::from(a)))
+}
+
+fn addr6(a: &str) -> json::Value {
+    obj("IPAddr6", obj("addr6", json::Value::from(a)))
+}
+
+fn subnet4(addr: u32, mask: u32) -> json::Value {
+    let mut s = json::Map::new();
+    s.insert("addr".to_string(), json::Value::from(addr));
+    s.insert("mask".to_string(), json::Value::from(mask));
+    obj("IPSubnet4", obj("ip4_subnet", obj("IP4Subnet", json::Value::Object(s))))
+}
+
+fn subnet6(addr: &str, mask: &str) -> json::Value {
+    let mut s = json::Map::new();
+    s.insert("addr".to_string(), json::Value::from(addr));
+    s.insert("mask".to_string(), json::Value::from(mask));
+    obj("IPSubnet6", obj("ip6_subnet", obj("IP6Subnet", json::Value::Object(s))))
+}
+
+/// The /64 mask.
+fn mask64() -> String {
+    (!0u128 << 64).to_string()
+}
+
+/// Router port 1, with 10.0.0.1/24 and 2001:db8::1/64.
+fn ovn() -> Ovn {
+    let mut ovn = Ovn::spawn(&[]);
+    ovn.add("LogicalRouter", row![TENANT, LROUTER, true, "lr1", "RouterRegular"]).unwrap();
+    ovn.add("LogicalRouterPort", row![TENANT, 1, "lrp1", LROUTER, "LRPRegular", MAC, true, "NoPeer", 0]).unwrap();
+    ovn.add("LRouterPortNetwork", row![TENANT, 1, subnet4(ip4(1), 0xffff_ff00)]).unwrap();
+    ovn.add("LRouterPortNetwork", row![TENANT, 1, subnet6(&ip6(1), &mask64())]).unwrap();
+    ovn
+}
+
+fn static_routes(ovn: &mut Ovn) -> Vec<json::Value> {
+    ovn.rows("Route").into_iter().filter(|r| r[3] != "NoIPAddr").collect()
+}
+
+fn connected_routes(ovn: &mut Ovn) -> Vec<json::Value> {
+    ovn.rows("Route").into_iter().filter(|r| r[3] == "NoIPAddr").collect()
+}
+
+/// A static route leaves through the network of its port that holds the next hop, and not
+/// through the port's other networks.
+#[test]
+fn routes_static_routes_through_the_network_of_the_next_hop() {
+    let mut ovn = ovn();
+    let prefix = subnet4(0xc0a8_0000, 0xffff_0000);
+    ovn.add("LogicalRouterStaticRoute", row![TENANT, LROUTER, prefix.clone(), addr4(ip4(254)), 1]).unwrap();
+    assert_eq!(static_routes(&mut ovn), vec![
+        row![TENANT, LROUTER, prefix.clone(), obj("SomeIPAddr", obj("addr", addr4(ip4(254)))), 1, MAC, addr4(ip4(1))]
+    ]);
+
+    let prefix6 = subnet6(&(0x2001_0db8_0001u128 << 80).to_string(), &(!0u128 << 80).to_string());
+    ovn.add("LogicalRouterStaticRoute", row![TENANT, LROUTER, prefix6.clone(), addr6(&ip6(254)), 1]).unwrap();
+    assert_eq!(static_routes(&mut ovn).len(), 2);
+    assert!(static_routes(&mut ovn).contains(
+        &row![TENANT, LROUTER, prefix6, obj("SomeIPAddr", obj("addr", addr6(&ip6(254)))), 1, MAC, addr6(&ip6(1))]));
+}
+
+#[test]
+fn drops_static_routes_whose_next_hop_is_on_no_network_of_the_port() {
+    let mut ovn = ovn();
+    ovn.add("LogicalRouterStaticRoute", row![TENANT, LROUTER, subnet4(0xc0a8_0000, 0xffff_0000), addr4(0x0a01_0001), 1]).unwrap();
+    assert_eq!(static_routes(&mut ovn), Vec::<json::Value>::new());
+}
+
+/// Connected routes are for the network of each port address, with the address itself as the
+/// port's.
+#[test]
+fn connected_routes_have_the_network_address() {
+    let mut ovn = ovn();
+    let mut expected = vec![
+        row![TENANT, LROUTER, subnet4(ip4(0), 0xffff_ff00), "NoIPAddr", 1, MAC, addr4(ip4(1))],
+        row![TENANT, LROUTER, subnet6(&ip6(0), &mask64()), "NoIPAddr", 1, MAC, addr6(&ip6(1))]
+    ];
+    expected.sort_by_key(|r| r.to_string());
+    assert_eq!(connected_routes(&mut ovn), expected);
+}
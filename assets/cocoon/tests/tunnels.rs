@@ -0,0 +1,127 @@
+//! Negotiation of tunnel encapsulations: `ChassisEncap`, `TunnelFromToEncap`, `TunnelFromTo` and
+//! `TunnelNoEncap` in `ovn.dl`.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::Ovn;
+
+/// Chassis `1..n+1`, each with a tunnel port.
+fn ovn(n: u32) -> Ovn {
+    let mut ovn = Ovn::spawn(&[]);
+    for c in 1..n + 1 {
+        ovn.add("Chassis", row![c, false, format!("hv{}", c), format!("192.168.0.{}", c)]).unwrap();
+        ovn.add("TunnelPort", row![c, 1, c, 0xc0a8_0000u32 | c]).unwrap();
+    };
+    ovn
+}
+
+fn encap(ovn: &mut Ovn, chassis: u32, encap: &str, preference: u16) {
+    ovn.add("ChassisEncap", row![chassis, encap, preference]).unwrap();
+}
+
+/// The tunnels, as `(from, to, encap)`, after checking that each goes to the tunnel port of
+/// its chassis and that `TunnelFromTo` has the same ones.
+fn tunnels(ovn: &mut Ovn) -> Vec<(u64, u64, String)> {
+    let encaps = ovn.rows("TunnelFromToEncap");
+    let plain: Vec<json::Value> = encaps.iter().map(|t| row![t[0].clone(), t[1].clone(), t[2].clone()]).collect();
+    assert_eq!(ovn.rows("TunnelFromTo"), plain);
+    encaps.iter().map(|t| {
+        let (from, to) = (t[0].as_u64().unwrap(), t[1].as_u64().unwrap());
+        assert_eq!(t[2].as_u64(), Some(0xc0a8_0000 | to), "{}", t);
+        (from, to, t[3].as_str().unwrap().to_string())
+    }).collect()
+}
+
+fn no_encap(ovn: &mut Ovn) -> Vec<(u64, u64)> {
+    ovn.rows("TunnelNoEncap").iter().map(|t| (t[0].as_u64().unwrap(), t[1].as_u64().unwrap())).collect()
+}
+
+fn both_ways(pairs: &[(u64, u64, &str)]) -> Vec<(u64, u64, String)> {
+    let mut v: Vec<_> = pairs.iter().flat_map(|&(a, b, e)| vec![(a, b, e.to_string()), (b, a, e.to_string())]).collect();
+    v.sort();
+    v
+}
+
+#[test]
+fn negotiates_the_cheapest_common_encap() {
+    let mut ovn = ovn(3);
+    encap(&mut ovn, 1, "EncapGeneve", 10);
+    encap(&mut ovn, 1, "EncapVXLAN", 5);
+    encap(&mut ovn, 2, "EncapGeneve", 10);
+    encap(&mut ovn, 2, "EncapVXLAN", 5);
+    encap(&mut ovn, 2, "EncapSTT", 1);
+    encap(&mut ovn, 3, "EncapGeneve", 10);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapVXLAN"), (1, 3, "EncapGeneve"), (2, 3, "EncapGeneve")]));
+    assert_eq!(no_encap(&mut ovn), vec![]);
+    // the sum of the preferences decides, not either end's alone
+    encap(&mut ovn, 1, "EncapSTT", 12);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapVXLAN"), (1, 3, "EncapGeneve"), (2, 3, "EncapGeneve")]));
+    ovn.del("ChassisEncap", row![1, "EncapSTT", 12]).unwrap();
+    encap(&mut ovn, 1, "EncapSTT", 8);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapSTT"), (1, 3, "EncapGeneve"), (2, 3, "EncapGeneve")]));
+}
+
+#[test]
+fn breaks_ties_alphabetically() {
+    let mut ovn = ovn(3);
+    for &e in &["EncapVXLAN", "EncapSTT", "EncapGeneve"] {
+        encap(&mut ovn, 1, e, 5);
+    };
+    encap(&mut ovn, 2, "EncapVXLAN", 5);
+    encap(&mut ovn, 2, "EncapSTT", 5);
+    encap(&mut ovn, 3, "EncapVXLAN", 5);
+    encap(&mut ovn, 3, "EncapGeneve", 5);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapSTT"), (1, 3, "EncapGeneve"), (2, 3, "EncapVXLAN")]));
+}
+
+#[test]
+fn flags_pairs_without_a_common_encap() {
+    let mut ovn = ovn(3);
+    encap(&mut ovn, 1, "EncapGeneve", 1);
+    encap(&mut ovn, 2, "EncapVXLAN", 1);
+    encap(&mut ovn, 3, "EncapGeneve", 1);
+    encap(&mut ovn, 3, "EncapVXLAN", 1);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 3, "EncapGeneve"), (2, 3, "EncapVXLAN")]));
+    assert_eq!(no_encap(&mut ovn), vec![(1, 2), (2, 1)]);
+    encap(&mut ovn, 2, "EncapGeneve", 1);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapGeneve"), (1, 3, "EncapGeneve"), (2, 3, "EncapGeneve")]));
+    assert_eq!(no_encap(&mut ovn), vec![]);
+}
+
+/// A chassis that advertises nothing supports Geneve alone, so tunnels survive a deployment
+/// that never writes `ChassisEncap`, and one that starts to.
+#[test]
+fn defaults_to_geneve_without_advertisements() {
+    let mut ovn = ovn(2);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapGeneve")]));
+    assert_eq!(no_encap(&mut ovn), vec![]);
+    // chassis 2 moves first, keeping Geneve for chassis 1
+    encap(&mut ovn, 2, "EncapVXLAN", 1);
+    encap(&mut ovn, 2, "EncapGeneve", 10);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapGeneve")]));
+    // then chassis 1
+    encap(&mut ovn, 1, "EncapVXLAN", 1);
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapVXLAN")]));
+    // a chassis without Geneve cannot reach one that advertises nothing
+    ovn.add("Chassis", row![3, false, "hv3", "192.168.0.3"]).unwrap();
+    ovn.add("TunnelPort", row![3, 1, 3, 0xc0a8_0003u32]).unwrap();
+    ovn.del("ChassisEncap", row![2, "EncapGeneve", 10]).unwrap();
+    assert_eq!(tunnels(&mut ovn), both_ways(&[(1, 2, "EncapVXLAN")]));
+    assert_eq!(no_encap(&mut ovn), vec![(1, 3), (2, 3), (3, 1), (3, 2)]);
+}
+
+/// Without advertisements, the tunnels are those from before `ChassisEncap`: from every
+/// chassis to every tunnel port on another switch, whether or not a chassis has that id.
+#[test]
+fn keeps_the_tunnels_of_deployments_that_do_not_advertise() {
+    let mut ovn = ovn(2);
+    ovn.add("TunnelPort", row![3, 1, 3, 0xc0a8_0003u32]).unwrap();
+    assert_eq!(ovn.rows("TunnelFromTo"), vec![row![1, 2, 0xc0a8_0002u32], row![1, 3, 0xc0a8_0003u32],
+                                              row![2, 1, 0xc0a8_0001u32], row![2, 3, 0xc0a8_0003u32]]);
+    assert_eq!(no_encap(&mut ovn), vec![]);
+}
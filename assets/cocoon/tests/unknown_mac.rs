@@ -0,0 +1,99 @@
+//! Flooding of unknown MACs: `LPortUnknownMACChassis` and `UnknownMACChassis` in `ovn.dl`.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::{obj, Ovn};
+
+const TENANT: u32 = 1;
+const LSWITCH: u64 = 10;
+
+fn ptype(s: &str) -> json::Value {
+    json::from_str(s).unwrap()
+}
+
+fn port(ovn: &mut Ovn, lport: u64, ptype: json::Value, unknown_addr: bool) {
+    ovn.add("LogicalSwitchPort", row![TENANT, lport, LSWITCH, ptype, format!("lsp{}", lport), true,
+                                      "NoDHCP4Options", "NoDHCP6Options", unknown_addr, 0]).unwrap();
+}
+
+fn bind(ovn: &mut Ovn, lport: u64, vport: u64, chassis: u32) {
+    ovn.add("VSwitchPort", row![vport, format!("vif{}", vport), chassis, vport as u16]).unwrap();
+    ovn.add("LPortBinding", row![TENANT, lport, vport]).unwrap();
+}
+
+/// Chassis 1 and 2, and failed chassis 3, with one switch holding a port of every type that
+/// accepts unknown MACs. Switch and port ids differ, so rows with the two swapped show up.
+fn ovn() -> Ovn {
+    let mut ovn = Ovn::spawn(&[]);
+    for &(c, failed) in &[(1u32, false), (2, false), (3, true)] {
+        ovn.add("Chassis", row![c, failed, format!("hv{}", c), format!("192.168.0.{}", c)]).unwrap();
+    };
+    ovn.add("LogicalSwitch", row![TENANT, LSWITCH, "LSwitchRegular", "ls", "NoSubnet"]).unwrap();
+    // A trunk VM, left out in favour of its VIF, which is bound on its own.
+    port(&mut ovn, 1, json::Value::from("LPortVM"), true);
+    bind(&mut ovn, 1, 100, 1);
+    port(&mut ovn, 2, ptype(r#"{"LPortVIF": {"parent": 1, "tag_request": 0, "tag": 0}}"#), true);
+    bind(&mut ovn, 2, 101, 1);
+    port(&mut ovn, 3, json::Value::from("LPortVM"), true);
+    bind(&mut ovn, 3, 102, 2);
+    // Does not accept unknown MACs.
+    port(&mut ovn, 4, json::Value::from("LPortVM"), false);
+    bind(&mut ovn, 4, 103, 2);
+    port(&mut ovn, 5, ptype(r#"{"LPortLocalnet": {"localnet": 1}}"#), true);
+    port(&mut ovn, 6, ptype(r#"{"LPortL2Gateway": {"pnet": 1, "chassis": 2}}"#), true);
+    port(&mut ovn, 7, ptype(r#"{"LPortRouter": {"rport": 1}}"#), true);
+    ovn
+}
+
+fn sorted(mut rows: Vec<json::Value>) -> Vec<json::Value> {
+    rows.sort_by_key(|r| r.to_string());
+    rows
+}
+
+fn port_rows(ovn: &mut Ovn, lport: u64) -> Vec<json::Value> {
+    ovn.rows("LPortUnknownMACChassis").into_iter().filter(|r| r[2].as_u64() == Some(lport)).collect()
+}
+
+/// The VM and VIF rows, as derived by the hand-written controller `ovn.dl` replaced. It wrote
+/// the switch first for these two port types only, so they are the reference for the others.
+#[test]
+fn vm_and_vif_rows_match_the_hand_written_controller() {
+    let mut ovn = ovn();
+    for &lport in &[1, 4] {
+        assert_eq!(port_rows(&mut ovn, lport), Vec::<json::Value>::new(), "port {}", lport);
+    };
+    assert_eq!(port_rows(&mut ovn, 2), vec![row![TENANT, LSWITCH, 2, 1, false]]);
+    assert_eq!(port_rows(&mut ovn, 3), vec![row![TENANT, LSWITCH, 3, 2, false]]);
+}
+
+#[test]
+fn every_port_type_writes_the_switch_first() {
+    let mut ovn = ovn();
+    assert_eq!(ovn.rows("LPortUnknownMACChassis"), sorted(vec![
+        row![TENANT, LSWITCH, 2, 1, false],
+        row![TENANT, LSWITCH, 3, 2, false],
+        row![TENANT, LSWITCH, 5, 1, true],
+        row![TENANT, LSWITCH, 5, 2, true],
+        row![TENANT, LSWITCH, 6, 2, false],
+        row![TENANT, LSWITCH, 7, 1, true],
+        row![TENANT, LSWITCH, 7, 2, true]
+    ]));
+}
+
+/// Localnet, router and L2 gateway ports used to write the port first, so their switch was
+/// flooded nowhere and a switch named after the port was flooded instead.
+#[test]
+fn floods_to_the_switch_on_every_live_chassis() {
+    let mut ovn = ovn();
+    assert_eq!(ovn.rows("UnknownMACChassis"), sorted(vec![
+        row![TENANT, 1, LSWITCH, "DSTLocal"],
+        row![TENANT, 1, LSWITCH, obj("DSTChassis", obj("chassis", json::Value::from(2)))],
+        row![TENANT, 2, LSWITCH, "DSTLocal"],
+        row![TENANT, 2, LSWITCH, obj("DSTChassis", obj("chassis", json::Value::from(1)))]
+    ]));
+}
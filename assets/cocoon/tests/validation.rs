@@ -0,0 +1,91 @@
+//! Facts rejected on `add`: the `Valid` impls of `ovn.rs` and the `check` clauses of `ovn.dl`.
+
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use serde_json as json;
+
+use common::Ovn;
+
+const TENANT: u32 = 1;
+
+/// Adds `row` to `rel`, expecting an error that contains `msg` and no new row.
+fn rejects(ovn: &mut Ovn, rel: &str, row: json::Value, msg: &str) {
+    let before = ovn.rows(rel);
+    match ovn.add(rel, row.clone()) {
+        Ok(()) => panic!("{} {} was accepted", rel, row),
+        Err(e) => assert!(e.contains(msg), "{} {}: {:?} does not mention {:?}", rel, row, e, msg)
+    };
+    assert_eq!(ovn.rows(rel), before, "{} {}", rel, row);
+}
+
+fn dhcp4(mask: u32, lease_time: u32) -> json::Value {
+    json::from_str(&format!(r#"{{"DHCP4Options": {{"cidr": {{"IP4Subnet": {{"addr": 167772160, "mask": {}}}}},
+                                "server_id": "10.0.0.1", "server_mac": "02:00:00:00:00:01",
+                                "router": "10.0.0.1", "lease_time": {}}}}}"#, mask, lease_time)).unwrap()
+}
+
+fn vif(parent: u64, tag_request: u16, tag: u16) -> json::Value {
+    json::from_str(&format!(r#"{{"LPortVIF": {{"parent": {}, "tag_request": {}, "tag": {}}}}}"#, parent, tag_request, tag)).unwrap()
+}
+
+fn port(lport: u64, ptype: json::Value) -> json::Value {
+    row![TENANT, lport, 1, ptype, format!("lsp{}", lport), true, "NoDHCP4Options", "NoDHCP6Options", false, 0]
+}
+
+#[test]
+fn rejects_non_contiguous_masks() {
+    let mut ovn = Ovn::spawn(&[]);
+    rejects(&mut ovn, "DHCPv4Options", row![TENANT, 1, dhcp4(0xff00_ff00, 3600)], "contiguous");
+    rejects(&mut ovn, "DHCPv4Options", row![TENANT, 1, dhcp4(0x00ff_ffff, 3600)], "contiguous");
+    ovn.add("DHCPv4Options", row![TENANT, 1, dhcp4(0xffff_ff00, 3600)]).unwrap();
+}
+
+#[test]
+fn rejects_vlan_ids_above_4095() {
+    let mut ovn = Ovn::spawn(&[]);
+    ovn.add("LogicalSwitchPort", port(1, json::Value::from("LPortVM"))).unwrap();
+    rejects(&mut ovn, "LogicalSwitchPort", port(2, vif(1, 4096, 0)), "tag_request 4096");
+    rejects(&mut ovn, "LogicalSwitchPort", port(2, vif(1, 0, 4096)), "tag 4096");
+    rejects(&mut ovn, "LogicalSwitchPort", port(2, vif(1, 65535, 65535)), "is not a VLAN id");
+    ovn.add("LogicalSwitchPort", port(2, vif(1, 4095, 4095))).unwrap();
+}
+
+#[test]
+fn rejects_a_vif_nested_in_itself() {
+    let mut ovn = Ovn::spawn(&[]);
+    rejects(&mut ovn, "LogicalSwitchPort", port(2, vif(2, 0, 0)), "a VIF cannot be nested in itself");
+    ovn.add("LogicalSwitchPort", port(1, json::Value::from("LPortVM"))).unwrap();
+    ovn.add("LogicalSwitchPort", port(2, vif(1, 0, 0))).unwrap();
+}
+
+#[test]
+fn rejects_a_zero_lease_time() {
+    let mut ovn = Ovn::spawn(&[]);
+    rejects(&mut ovn, "DHCPv4Options", row![TENANT, 1, dhcp4(0xffff_ff00, 0)], "lease_time must not be 0");
+    ovn.add("DHCPv4Options", row![TENANT, 1, dhcp4(0xffff_ff00, 1)]).unwrap();
+}
+
+#[test]
+fn rejects_protocols_other_than_tcp_udp_and_sctp() {
+    let mut ovn = Ovn::spawn(&[]);
+    for &p in &[0u8, 1, 58, 255] {
+        rejects(&mut ovn, "LoadBalancer", row![TENANT, 1, "lb", p], "protocol must be TCP (6), UDP (17) or SCTP (132)");
+    };
+    for &(lb, p) in &[(1u64, 6u8), (2, 17), (3, 132)] {
+        ovn.add("LoadBalancer", row![TENANT, lb, "lb", p]).unwrap();
+    };
+    assert_eq!(ovn.rows("LoadBalancer").len(), 3);
+}
+
+#[test]
+fn rejects_mac_prefixes_that_are_multicast_global_or_too_wide() {
+    let mut ovn = Ovn::spawn(&[]);
+    rejects(&mut ovn, "DynMACPrefix", row![TENANT, 0x0100_0000], "prefix must be a 24-bit OUI");
+    rejects(&mut ovn, "DynMACPrefix", row![TENANT, 0x0b_0000], "prefix must not have the multicast bit set");
+    rejects(&mut ovn, "DynMACPrefix", row![TENANT, 0x08_0000], "prefix must have the locally administered bit set");
+    ovn.add("DynMACPrefix", row![TENANT, 0x0a_0000]).unwrap();
+    ovn.add("DynMACPrefix", row![TENANT, 0xfe_1234]).unwrap();
+}
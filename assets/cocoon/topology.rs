@@ -0,0 +1,394 @@
+//! Synthetic OVN topologies, as streams of requests for load testing.
+//!
+//! `Topology::build` lays out `Sizes` worth of chassis, logical switches with their ports
+//! bound to VIFs on random chassis, routers attached to the switches with NAT rules, ACLs and
+//! load balancers. `Topology::churn` then changes it the way a running cloud does: VMs migrate
+//! to another chassis (a new `VSwitchPort`, and `LPortBinding` rebound to it), ports are
+//! disabled and enabled, and ACLs are edited.
+//!
+//! Both return transactions as lists of `add` and `del` requests in the JSON form the request
+//! loop reads, with addresses in their text form. Namespaced relations get the tenant as their
+//! first column; every id is unique across tenants.
+
+use std::fmt;
+
+use serde_json as json;
+
+/// How big a topology to generate.
+#[derive(Clone, Debug, Serialize)]
+pub struct Sizes {
+    pub chassis: u32,
+    pub tenants: u32,
+    /// Logical switches, spread over the tenants.
+    pub switches: u64,
+    /// VM ports on each logical switch.
+    pub ports: u64,
+    /// Logical routers, spread over the tenants; each is attached to some of its tenant's
+    /// switches.
+    pub routers: u64,
+    /// NAT rules on each router.
+    pub nats: u64,
+    /// ACLs on each logical switch.
+    pub acls: u64,
+    /// Load balancers, spread over the tenants.
+    pub lbs: u64,
+    pub seed: u64
+}
+
+impl Default for Sizes {
+    fn default() -> Sizes {
+        Sizes { chassis: 4, tenants: 1, switches: 4, ports: 8, routers: 1, nats: 2, acls: 4, lbs: 2, seed: 1 }
+    }
+}
+
+impl Sizes {
+    /// Takes `--chassis N`, `--tenants N`, `--switches N`, `--ports N`, `--routers N`, `--nats N`,
+    /// `--acls N`, `--lbs N` and `--seed N` out of `args`, defaulting the others.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Sizes, String> {
+        let d = Sizes::default();
+        let s = Sizes {
+            chassis:  flag(args, "--chassis")?.unwrap_or(d.chassis as u64) as u32,
+            tenants:  flag(args, "--tenants")?.unwrap_or(d.tenants as u64) as u32,
+            switches: flag(args, "--switches")?.unwrap_or(d.switches),
+            ports:    flag(args, "--ports")?.unwrap_or(d.ports),
+            routers:  flag(args, "--routers")?.unwrap_or(d.routers),
+            nats:     flag(args, "--nats")?.unwrap_or(d.nats),
+            acls:     flag(args, "--acls")?.unwrap_or(d.acls),
+            lbs:      flag(args, "--lbs")?.unwrap_or(d.lbs),
+            seed:     flag(args, "--seed")?.unwrap_or(d.seed)
+        };
+        if s.chassis == 0 || s.tenants == 0 {
+            return Err(format!("--chassis and --tenants must be at least 1"));
+        } else if s.ports > 250 {
+            return Err(format!("--ports {}: a switch's /24 subnet has room for 250 ports", s.ports));
+        } else if s.switches > 65536 {
+            return Err(format!("--switches {}: switch subnets are the /24s of 10.0.0.0/8", s.switches));
+        };
+        Ok(s)
+    }
+
+    /// `k` times as many chassis, switches, routers and load balancers.
+    pub fn scaled(&self, k: u64) -> Result<Sizes, String> {
+        let s = Sizes { chassis: self.chassis * k as u32, switches: self.switches * k, routers: self.routers * k, lbs: self.lbs * k, .. self.clone() };
+        if s.switches > 65536 {
+            return Err(format!("scale {}: {} switches, but switch subnets are the /24s of 10.0.0.0/8", k, s.switches));
+        };
+        Ok(s)
+    }
+}
+
+fn flag(args: &mut Vec<String>, name: &str) -> Result<Option<u64>, String> {
+    match args.iter().position(|a| a == name) {
+        Some(i) if i + 1 < args.len() => {
+            let v = args.remove(i + 1);
+            args.remove(i);
+            v.parse().map(Some).map_err(|_| format!("{}: {} is not a number", name, v))
+        },
+        Some(_) => Err(format!("{}: missing number", name)),
+        None    => Ok(None)
+    }
+}
+
+/// A xorshift generator, so that a seed always generates the same stream.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+/// A VM port, and the VIF it is bound to.
+struct Port {
+    tenant: u32,
+    id: u64,
+    lswitch: u64,
+    enabled: bool,
+    vport: u64,
+    chassis: u32,
+    portnum: u16
+}
+
+/// The ACLs of one switch.
+struct Acls {
+    tenant: u32,
+    lswitch: u64,
+    subnet: u64,
+    rules: Vec<(u16, bool, bool)>
+}
+
+/// A random topology, and the state `churn` changes.
+pub struct Topology {
+    sizes: Sizes,
+    rng: Rng,
+    ports: Vec<Port>,
+    acls: Vec<Acls>,
+    /// The next VIF port number of each chassis.
+    portnums: Vec<u16>,
+    next_id: u64
+}
+
+/// One transaction's `add` and `del` requests.
+pub type Txn = Vec<json::Value>;
+
+/// The kinds of change `churn` makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Churn {
+    Migrate,
+    Toggle,
+    AclEdit
+}
+
+impl fmt::Display for Churn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Churn::Migrate => write!(f, "migrate"),
+            Churn::Toggle  => write!(f, "toggle"),
+            Churn::AclEdit => write!(f, "acl_edit")
+        }
+    }
+}
+
+fn req(cmd: &str, rel: &str, row: Vec<json::Value>) -> json::Value {
+    let mut f = json::Map::new();
+    f.insert(rel.to_string(), json::Value::Array(row));
+    let mut r = json::Map::new();
+    r.insert(cmd.to_string(), json::Value::Object(f));
+    json::Value::Object(r)
+}
+
+fn add(rel: &str, row: Vec<json::Value>) -> json::Value {
+    req("add", rel, row)
+}
+
+fn del(rel: &str, row: Vec<json::Value>) -> json::Value {
+    req("del", rel, row)
+}
+
+/// An enum value: a string for a variant without fields, `{"Variant": {fields}}` otherwise.
+fn variant(name: &str, fields: Vec<(&str, json::Value)>) -> json::Value {
+    if fields.is_empty() {
+        return json::Value::from(name);
+    };
+    let mut m = json::Map::new();
+    m.insert(name.to_string(), json::Value::Object(fields.into_iter().map(|(f, v)| (f.to_string(), v)).collect()));
+    json::Value::Object(m)
+}
+
+fn lambda(s: String) -> json::Value {
+    variant("__Lambda", vec![("__lambda_string", json::Value::from(s))])
+}
+
+/// The `a.b` of switch `k`'s subnet, `10.a.b.0/24`.
+fn net(k: u64) -> String {
+    format!("10.{}.{}", k >> 8, k & 0xff)
+}
+
+fn mac(id: u64) -> u64 {
+    0x0200_0000_0000 | id
+}
+
+macro_rules! row {
+    ($($v:expr),*) => { vec![$(json::Value::from($v)),*] }
+}
+
+impl Topology {
+    pub fn new(sizes: Sizes) -> Topology {
+        let seed = sizes.seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        let chassis = sizes.chassis as usize;
+        Topology { sizes: sizes, rng: Rng(seed), ports: Vec::new(), acls: Vec::new(), portnums: vec![2; chassis], next_id: 1 }
+    }
+
+    fn id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    fn chassis(&mut self) -> u32 {
+        self.rng.below(self.sizes.chassis as u64) as u32 + 1
+    }
+
+    /// A new VIF on `chassis`, as its id, port number and `VSwitchPort` row.
+    fn vif(&mut self, chassis: u32) -> (u64, u16, Vec<json::Value>) {
+        let (vport, portnum) = (self.id(), self.portnums[chassis as usize - 1]);
+        self.portnums[chassis as usize - 1] = portnum.wrapping_add(1).max(2);
+        (vport, portnum, row![vport, format!("vif{}", vport), chassis, portnum])
+    }
+
+    fn lsp_row(p: &Port) -> Vec<json::Value> {
+        let mut r = row![p.tenant, p.id, p.lswitch];
+        r.push(variant("LPortVM", vec![]));
+        r.extend(row![format!("lsp{}", p.id), p.enabled, "NoDHCP4Options", "NoDHCP6Options", false, 0]);
+        r
+    }
+
+    fn acl_row(a: &Acls, i: usize) -> Vec<json::Value> {
+        let (priority, from, allow) = a.rules[i];
+        let cond = format!("ip4.{} == 10.{}.{}.0/28 && tcp.dst == {}", if from { "src" } else { "dst" },
+                           a.subnet >> 8, a.subnet & 0xff, 1000 + i);
+        let mut r = row![a.tenant, a.lswitch, priority, if from { "ACLFrom" } else { "ACLTo" }];
+        r.push(lambda(cond));
+        r.push(json::Value::from(if allow { "ACLAllowRelated" } else { "ACLDrop" }));
+        r
+    }
+
+    /// The whole topology: the chassis with their tunnels and encapsulations, then one transaction per switch with
+    /// its ports and ACLs, per router with its ports and NAT rules, and per load balancer.
+    pub fn build(&mut self) -> Vec<Txn> {
+        let sz = self.sizes.clone();
+        let mut txns = Vec::new();
+
+        let mut txn = Vec::new();
+        for c in 1..sz.chassis + 1 {
+            txn.push(add("Chassis", row![c, false, format!("chassis{}", c), format!("192.168.{}.{}", c >> 8, c & 0xff)]));
+            txn.push(add("TunnelPort", row![self.id(), 1, c, 0xc0a8_0000u32 | c]));
+            // every chassis supports Geneve, and the odd ones prefer VXLAN among themselves
+            txn.push(add("ChassisEncap", row![c, "EncapGeneve", 10]));
+            if c % 2 == 1 {
+                txn.push(add("ChassisEncap", row![c, "EncapVXLAN", 5]));
+            };
+        };
+        txns.push(txn);
+
+        // switches are numbered k in 0..switches, and belong to tenant k % tenants
+        let mut switches = Vec::new();
+        for k in 0..sz.switches {
+            let (tenant, ls) = ((k % sz.tenants as u64) as u32, self.id());
+            switches.push((tenant, ls, k));
+            let mut txn = vec![add("LogicalSwitch", row![tenant, ls, "LSwitchRegular", format!("ls{}", ls), format!("{}.0/24", net(k))])];
+            for j in 0..sz.ports {
+                let (id, chassis) = (self.id(), self.chassis());
+                let (vport, portnum, vsp) = self.vif(chassis);
+                let p = Port { tenant: tenant, id: id, lswitch: ls, enabled: true, vport: vport, chassis: chassis, portnum: portnum };
+                txn.push(add("LogicalSwitchPort", Topology::lsp_row(&p)));
+                txn.push(add("LogicalSwitchPortMAC", row![tenant, id, mac(id)]));
+                txn.push(add("LogicalSwitchPortIP", row![tenant, id, mac(id), format!("{}.{}", net(k), j + 2)]));
+                txn.push(add("PortSecurityMAC", row![tenant, id, mac(id)]));
+                txn.push(add("VSwitchPort", vsp));
+                txn.push(add("LPortBinding", row![tenant, id, vport]));
+                self.ports.push(p);
+            };
+            let acls = Acls {
+                tenant: tenant, lswitch: ls, subnet: k,
+                rules: (0..sz.acls).map(|i| (1000 + i as u16, i % 2 == 0, i % 3 != 2)).collect()
+            };
+            for i in 0..acls.rules.len() {
+                txn.push(add("ACL", Topology::acl_row(&acls, i)));
+            };
+            self.acls.push(acls);
+            txns.push(txn);
+        };
+
+        // router r belongs to tenant r % tenants, and is attached to every routers-th switch of
+        // that tenant
+        let mut routers = Vec::new();
+        for r in 0..sz.routers {
+            let (tenant, lr) = ((r % sz.tenants as u64) as u32, self.id());
+            routers.push((tenant, lr));
+            let mut txn = vec![add("LogicalRouter", row![tenant, lr, true, format!("lr{}", lr), "RouterRegular"])];
+            let (nth, count) = (r / sz.tenants as u64, (sz.routers - tenant as u64 + sz.tenants as u64 - 1) / sz.tenants as u64);
+            let attached: Vec<(u64, u64)> = switches.iter().filter(|s| s.0 == tenant).enumerate()
+                .filter(|&(i, _)| i as u64 % count == nth).map(|(_, s)| (s.1, s.2)).collect();
+            for &(ls, k) in &attached {
+                let (rp, lsp) = (self.id() as u32, self.id());
+                let mut lrp = row![tenant, rp, format!("lrp{}", rp), lr];
+                lrp.push(variant("LRPRegular", vec![]));
+                lrp.extend(row![mac(rp as u64), true, "NoPeer", 0]);
+                txn.push(add("LogicalRouterPort", lrp));
+                txn.push(add("LRouterPortNetwork", row![tenant, rp, format!("{}.1/24", net(k))]));
+                let mut sp = row![tenant, lsp, ls];
+                sp.push(variant("LPortRouter", vec![("rport", json::Value::from(rp))]));
+                sp.extend(row![format!("lsp-lrp{}", rp), true, "NoDHCP4Options", "NoDHCP6Options", false, 0]);
+                txn.push(add("LogicalSwitchPort", sp));
+            };
+            for n in 0..sz.nats {
+                let k = if attached.is_empty() { 0 } else { attached[n as usize % attached.len()].1 };
+                let external = 0xac10_0000u32 | ((lr as u32 & 0xff) << 8) | (n as u32 & 0xff);
+                txn.push(add("NAT", row![tenant, lr, if n % 2 == 0 { "SNAT" } else { "DNAT_SNAT" }, external, "NoMACAddr",
+                                         format!("{}.0/24", net(k)), "NoLPortId"]));
+            };
+            txns.push(txn);
+        };
+
+        for b in 0..sz.lbs {
+            let tenant = (b % sz.tenants as u64) as u32;
+            let mine: Vec<&(u32, u64, u64)> = switches.iter().filter(|s| s.0 == tenant).collect();
+            if mine.is_empty() { continue };
+            let &(_, ls, k) = mine[self.rng.below(mine.len() as u64) as usize];
+            let lb = self.id();
+            let vip = format!("172.16.{}.{}:80", b >> 8 & 0xff, b & 0xff);
+            let mut txn = vec![add("LoadBalancer", row![tenant, lb, format!("lb{}", lb), 6]),
+                               add("LBVIP", row![tenant, lb, vip.clone()]),
+                               add("LBSwitch", row![tenant, lb, ls])];
+            for j in 0..sz.ports.min(3) {
+                txn.push(add("LBIP", row![tenant, lb, vip.clone(), format!("{}.{}:8080", net(k), j + 2)]));
+            };
+            if let Some(&(_, lr)) = routers.iter().find(|r| r.0 == tenant) {
+                txn.push(add("LBRouter", row![tenant, lb, lr]));
+            };
+            txns.push(txn);
+        };
+        txns
+    }
+
+    /// One random change to the topology, and its kind; `None` if the topology has nothing to
+    /// change.
+    pub fn churn(&mut self) -> Option<(Churn, Txn)> {
+        let first = self.rng.below(3);
+        for k in 0..3 {
+            let change = match (first + k) % 3 {
+                0 => self.migrate().map(|t| (Churn::Migrate, t)),
+                1 => self.toggle().map(|t| (Churn::Toggle, t)),
+                _ => self.acl_edit().map(|t| (Churn::AclEdit, t))
+            };
+            if change.is_some() { return change };
+        };
+        None
+    }
+
+    /// Moves a VM to a VIF on another chassis.
+    fn migrate(&mut self) -> Option<Txn> {
+        if self.ports.is_empty() || self.sizes.chassis < 2 { return None };
+        let i = self.rng.below(self.ports.len() as u64) as usize;
+        let mut chassis = self.chassis();
+        while chassis == self.ports[i].chassis {
+            chassis = self.chassis();
+        };
+        let (vport, portnum, vsp) = self.vif(chassis);
+        let p = &mut self.ports[i];
+        let txn = vec![add("VSwitchPort", vsp),
+                       del("LPortBinding", row![p.tenant, p.id, p.vport]),
+                       add("LPortBinding", row![p.tenant, p.id, vport]),
+                       del("VSwitchPort", row![p.vport, format!("vif{}", p.vport), p.chassis, p.portnum])];
+        p.vport = vport;
+        p.chassis = chassis;
+        p.portnum = portnum;
+        Some(txn)
+    }
+
+    /// Disables an enabled port, or enables a disabled one.
+    fn toggle(&mut self) -> Option<Txn> {
+        if self.ports.is_empty() { return None };
+        let i = self.rng.below(self.ports.len() as u64) as usize;
+        let mut txn = vec![del("LogicalSwitchPort", Topology::lsp_row(&self.ports[i]))];
+        self.ports[i].enabled = !self.ports[i].enabled;
+        txn.push(add("LogicalSwitchPort", Topology::lsp_row(&self.ports[i])));
+        Some(txn)
+    }
+
+    /// Replaces an ACL with one of the other action, in the other direction.
+    fn acl_edit(&mut self) -> Option<Txn> {
+        let with_rules: Vec<usize> = (0..self.acls.len()).filter(|&i| !self.acls[i].rules.is_empty()).collect();
+        if with_rules.is_empty() { return None };
+        let a = with_rules[self.rng.below(with_rules.len() as u64) as usize];
+        let i = self.rng.below(self.acls[a].rules.len() as u64) as usize;
+        let mut txn = vec![del("ACL", Topology::acl_row(&self.acls[a], i))];
+        let (priority, from, allow) = self.acls[a].rules[i];
+        self.acls[a].rules[i] = (priority, !from, !allow);
+        txn.push(add("ACL", Topology::acl_row(&self.acls[a], i)));
+        Some(txn)
+    }
+}